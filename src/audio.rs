@@ -0,0 +1,99 @@
+use crate::pitch::Pitch;
+use std::f64::consts::TAU;
+
+pub const SAMPLE_RATE: u32 = 44_100;
+/// how long the reference tone is held
+pub const REFERENCE_SECONDS: f64 = 5.0;
+/// fade at both ends so the tone starts and stops without a click
+const FADE_SECONDS: f64 = 0.05;
+
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+#[display(
+    fmt = "invalid frequency `{}`, expected a positive number of Hz such as 440",
+    input
+)]
+pub struct ParseFrequencyError {
+    #[error(not(source))]
+    pub input: String,
+}
+
+/// a finite frequency above 0 Hz, such as `442` or `432.5`
+pub fn parse_frequency(input: &str) -> Result<f64, ParseFrequencyError> {
+    input
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|frequency| frequency.is_finite() && *frequency > 0.0)
+        .ok_or_else(|| ParseFrequencyError {
+            input: input.to_string(),
+        })
+}
+
+/// a sine at `frequency` Hz as 16 bit samples at half volume, faded in and out
+pub fn sine_samples(frequency: f64, seconds: f64) -> Vec<i16> {
+    let rate = SAMPLE_RATE as f64;
+    let count = (seconds * rate) as usize;
+    let amplitude = i16::MAX as f64 / 2.0;
+    (0..count)
+        .map(|index| {
+            let envelope = (index.min(count - index) as f64 / (FADE_SECONDS * rate)).min(1.0);
+            let phase = TAU * frequency * index as f64 / rate;
+            (phase.sin() * envelope * amplitude) as i16
+        })
+        .collect()
+}
+
+/// a mono 16 bit PCM WAV file of the samples
+pub fn wav_file(samples: &[i16]) -> Vec<u8> {
+    let data_length = (samples.len() * 2) as u32;
+    let mut bytes = b"RIFF".to_vec();
+    bytes.extend((36 + data_length).to_le_bytes());
+    bytes.extend(b"WAVEfmt ");
+    bytes.extend(16u32.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(SAMPLE_RATE.to_le_bytes());
+    bytes.extend((SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend(2u16.to_le_bytes());
+    bytes.extend(16u16.to_le_bytes());
+    bytes.extend(b"data");
+    bytes.extend(data_length.to_le_bytes());
+    bytes.extend(samples.iter().flat_map(|sample| sample.to_le_bytes()));
+    bytes
+}
+
+/// [REFERENCE_SECONDS] of `pitch` tuned with A4 at `a4` Hz, as a WAV file
+pub fn reference_tone(pitch: Pitch, a4: f64) -> Vec<u8> {
+    wav_file(&sine_samples(pitch.frequency_at(a4), REFERENCE_SECONDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::Note;
+
+    #[test]
+    fn test_reference_tone() {
+        let samples = sine_samples(441.0, 1.0);
+        assert_eq!(samples.len(), SAMPLE_RATE as usize);
+        let crossings = samples
+            .windows(2)
+            .filter(|pair| (pair[0] < 0) != (pair[1] < 0))
+            .count();
+        assert!((880..=884).contains(&crossings));
+        let wav = reference_tone(Pitch::new(Note::A, 4), 440.0);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        let seconds = (wav.len() - 44) as f64 / (SAMPLE_RATE as f64 * 2.0);
+        assert_eq!(seconds, REFERENCE_SECONDS);
+    }
+
+    #[test]
+    fn test_parse_frequency() {
+        assert_eq!(parse_frequency("440"), Ok(440.0));
+        assert_eq!(parse_frequency(" 432.5 "), Ok(432.5));
+        for input in ["0", "-440", "NaN", "inf", "A4"] {
+            assert!(parse_frequency(input).is_err(), "{input}");
+        }
+    }
+}
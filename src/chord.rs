@@ -0,0 +1,687 @@
+use crate::note::{note_bitmask, split_note_prefix, Note, NoteInput, NoteSet};
+use crate::pitch::Pitch;
+use crate::scale::{scales_containing, Scale, ScaleMode};
+use clap::ValueEnum;
+use derive_more::{Display, Error};
+use itertools::Itertools;
+use std::str::FromStr;
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
+
+#[derive(Debug, EnumIter, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ChordKind {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Sus2,
+    Sus4,
+    Dominant7,
+    Major7,
+    Minor7,
+    HalfDiminished7,
+    Diminished7,
+}
+
+impl ChordKind {
+    /// accepted suffixes, the first one is used for display
+    pub fn symbols(self) -> &'static [&'static str] {
+        match self {
+            ChordKind::Major => &["", "maj"],
+            ChordKind::Minor => &["m", "min"],
+            ChordKind::Diminished => &["dim", "o"],
+            ChordKind::Augmented => &["aug", "+"],
+            ChordKind::Sus2 => &["sus2"],
+            ChordKind::Sus4 => &["sus4", "sus"],
+            ChordKind::Dominant7 => &["7"],
+            ChordKind::Major7 => &["maj7", "M7"],
+            ChordKind::Minor7 => &["m7", "min7"],
+            ChordKind::HalfDiminished7 => &["m7b5", "ø"],
+            ChordKind::Diminished7 => &["dim7", "o7"],
+        }
+    }
+
+    pub fn intervals(self) -> Vec<usize> {
+        match self {
+            ChordKind::Major => vec![0, 4, 7],
+            ChordKind::Minor => vec![0, 3, 7],
+            ChordKind::Diminished => vec![0, 3, 6],
+            ChordKind::Augmented => vec![0, 4, 8],
+            ChordKind::Sus2 => vec![0, 2, 7],
+            ChordKind::Sus4 => vec![0, 5, 7],
+            ChordKind::Dominant7 => vec![0, 4, 7, 10],
+            ChordKind::Major7 => vec![0, 4, 7, 11],
+            ChordKind::Minor7 => vec![0, 3, 7, 10],
+            ChordKind::HalfDiminished7 => vec![0, 3, 6, 10],
+            ChordKind::Diminished7 => vec![0, 3, 6, 9],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum ChordSize {
+    Triad,
+    Seventh,
+    Ninth,
+}
+
+impl ChordSize {
+    pub fn note_count(self) -> usize {
+        match self {
+            ChordSize::Triad => 3,
+            ChordSize::Seventh => 4,
+            ChordSize::Ninth => 5,
+        }
+    }
+}
+
+const ROMAN_NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+/// upper case roman numeral for a 1-based scale degree
+pub fn roman_numeral(degree: usize) -> &'static str {
+    ROMAN_NUMERALS[(degree - 1) % ROMAN_NUMERALS.len()]
+}
+
+/// 1-based degree of a roman numeral in either case
+pub fn parse_roman_numeral(numeral: &str) -> Option<usize> {
+    ROMAN_NUMERALS
+        .iter()
+        .position(|candidate| candidate.eq_ignore_ascii_case(numeral))
+        .map(|index| index + 1)
+}
+
+/// quality of a triad stacked from two thirds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+}
+
+impl ChordQuality {
+    /// `None` unless the notes are a root and two stacked major or minor thirds
+    pub fn of_triad(notes: &[Note]) -> Option<Self> {
+        let [root, third, fifth] = notes else {
+            return None;
+        };
+        match (
+            root.ascending_distance(*third),
+            root.ascending_distance(*fifth),
+        ) {
+            (4, 7) => Some(ChordQuality::Major),
+            (3, 7) => Some(ChordQuality::Minor),
+            (3, 6) => Some(ChordQuality::Diminished),
+            (4, 8) => Some(ChordQuality::Augmented),
+            _ => None,
+        }
+    }
+
+    /// roman numeral of the (1-based) degree, lower case for minor and diminished: ii, vii°
+    pub fn numeral(self, degree: usize) -> String {
+        let numeral = roman_numeral(degree);
+        match self {
+            ChordQuality::Major => numeral.to_string(),
+            ChordQuality::Minor => numeral.to_lowercase(),
+            ChordQuality::Diminished => format!("{}°", numeral.to_lowercase()),
+            ChordQuality::Augmented => format!("{numeral}+"),
+        }
+    }
+}
+
+/// which diatonic triads to mark on the neck, each in the color of its degree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriadOverlay {
+    /// the triads on these (1-based) degrees, a shared note taking the first one's color
+    Degrees(Vec<usize>),
+    /// every diatonic triad, in degree order
+    All,
+}
+
+impl TriadOverlay {
+    /// the (1-based) degrees of the marked triads of `scale`, in order of precedence
+    pub fn degrees(&self, scale: &Scale) -> Vec<usize> {
+        match self {
+            TriadOverlay::Degrees(degrees) => degrees.clone(),
+            TriadOverlay::All => (1..=scale.diatonic_degrees().len()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(
+    fmt = "invalid triad overlay `{}`, expected roman numerals such as `I,IV` or `all`",
+    input
+)]
+pub struct ParseTriadOverlayError {
+    #[error(not(source))]
+    pub input: String,
+}
+
+impl FromStr for TriadOverlay {
+    type Err = ParseTriadOverlayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            all if all.eq_ignore_ascii_case("all") => Ok(TriadOverlay::All),
+            numerals => numerals
+                .split(',')
+                .map(|numeral| parse_roman_numeral(numeral.trim()))
+                .collect::<Option<Vec<_>>>()
+                .map(TriadOverlay::Degrees)
+                .ok_or_else(|| ParseTriadOverlayError {
+                    input: s.to_string(),
+                }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub root: Note,
+    pub kind: ChordKind,
+    /// the slash bass, if it was given separately from the root
+    pub bass: Option<Note>,
+}
+
+impl std::fmt::Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.root, self.kind.symbols()[0])?;
+        match self.bass {
+            Some(bass) => write!(f, "/{bass}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Chord {
+    pub fn notes(&self) -> Vec<Note> {
+        self.kind
+            .intervals()
+            .into_iter()
+            .map(|interval| self.root.offset_by(interval as _))
+            .collect()
+    }
+
+    pub fn bass(&self) -> Note {
+        self.bass.unwrap_or(self.root)
+    }
+
+    /// the chord with its `inversion`th tone (0 being the root) in the bass, `None` when
+    /// it has fewer tones than that
+    pub fn inverted(&self, inversion: usize) -> Option<Chord> {
+        let bass = *self.notes().get(inversion)?;
+        Some(Chord {
+            bass: (bass != self.root).then_some(bass),
+            ..*self
+        })
+    }
+
+    /// the chord tones rotated so the bass comes first, a bass outside the chord being
+    /// added below them
+    pub fn voiced_notes(&self) -> Vec<Note> {
+        let mut notes = self.notes();
+        match self.inversion() {
+            Some(inversion) => notes.rotate_left(inversion),
+            None => notes.insert(0, self.bass()),
+        }
+        notes
+    }
+
+    /// [Chord::voiced_notes] as rising pitches from the bass in `octave`, every moved
+    /// tone going up an octave as needed to stay above the previous one
+    pub fn pitches(&self, octave: i32) -> Vec<Pitch> {
+        let mut pitches: Vec<Pitch> = Vec::new();
+        for note in self.voiced_notes() {
+            let mut pitch = Pitch::new(note, octave);
+            while pitches.last().is_some_and(|previous| pitch <= *previous) {
+                pitch = pitch.offset_by(Note::COUNT as i32);
+            }
+            pitches.push(pitch);
+        }
+        pitches
+    }
+
+    /// the 9th, 11th and 13th (altered as the scale has them, e.g. `b9` or `#11`) that are
+    /// in `scale`, leaving out chord tones and avoid notes a half step above a chord tone
+    /// (dominant chords keep their altered b9 and b13)
+    pub fn available_tensions(&self, scale: &Scale) -> Vec<(&'static str, Note)> {
+        const TENSIONS: [(&str, usize); 7] = [
+            ("b9", 1),
+            ("9", 2),
+            ("#9", 3),
+            ("11", 5),
+            ("#11", 6),
+            ("b13", 8),
+            ("13", 9),
+        ];
+        let notes = self.notes();
+        let scale_notes = scale.notes();
+        TENSIONS
+            .iter()
+            .map(|(name, semitones)| (*name, self.root.offset_by(*semitones as i32)))
+            .filter(|(_, note)| scale_notes.contains(note) && !notes.contains(note))
+            .filter(|(name, note)| {
+                let altered = self.kind == ChordKind::Dominant7 && name.starts_with('b');
+                altered || !notes.contains(&note.offset_by(-1))
+            })
+            .collect()
+    }
+
+    /// 0 for root position, 1 for the 3rd in the bass and so on,
+    /// `None` when the bass is not a chord tone
+    pub fn inversion(&self) -> Option<usize> {
+        self.notes().iter().position(|note| *note == self.bass())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(fmt = "invalid chord `{}`", input)]
+pub struct ParseChordError {
+    #[error(not(source))]
+    pub input: String,
+}
+
+impl FromStr for Chord {
+    type Err = ParseChordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseChordError {
+            input: s.to_string(),
+        };
+        let (symbol, bass) = match s.trim().split_once('/') {
+            Some((symbol, bass)) => (symbol, Some(bass.parse().map_err(|_| error())?)),
+            None => (s.trim(), None),
+        };
+        let (root, suffix) = split_note_prefix(symbol).ok_or_else(error)?;
+        let kind = ChordKind::iter()
+            .find(|kind| kind.symbols().contains(&suffix))
+            .ok_or_else(error)?;
+        Ok(Self { root, kind, bass })
+    }
+}
+
+/// a chord given by its symbol (`Am7`, `C/E`) or by distinct notes, root first (`A,C,E,G`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordInput {
+    Symbol(Chord),
+    Notes(NoteSet),
+}
+
+impl FromStr for ChordInput {
+    type Err = ParseChordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, NoteInput::English)
+    }
+}
+
+impl ChordInput {
+    /// like [ChordInput::from_str] with the notes of a note list written in the `notes`
+    /// convention; symbols always use English note names
+    pub fn parse_with(input: &str, notes: NoteInput) -> Result<Self, ParseChordError> {
+        match input.contains(',') {
+            true => NoteSet::parse_with(input, notes)
+                .map(ChordInput::Notes)
+                .map_err(|_| ParseChordError {
+                    input: input.to_string(),
+                }),
+            false => input.parse().map(ChordInput::Symbol),
+        }
+    }
+}
+
+impl Chord {
+    /// the chord of exactly these distinct notes with the first one as its root
+    pub fn from_notes(notes: &[Note]) -> Option<Chord> {
+        let root = *notes.first()?;
+        match notes.iter().unique().count() == notes.len() {
+            true => ChordKind::iter()
+                .map(|kind| Chord {
+                    root,
+                    kind,
+                    bass: None,
+                })
+                .find(|chord| {
+                    let tones = chord.notes();
+                    tones.len() == notes.len() && notes.iter().all(|note| tones.contains(note))
+                }),
+            false => None,
+        }
+    }
+}
+
+/// the root a tritone away from `chord_root`, whose dominant shares the third and the seventh
+/// of the dominant on `chord_root` (G7 and Db7 both hold B and F)
+pub fn tritone_sub(chord_root: Note) -> Note {
+    chord_root.offset_by(6)
+}
+
+/// `G7 -> Db7 (Db, F, Ab, B), sharing F, B`: the substitute of `chord` spelled with flats;
+/// `None` unless `chord` is a dominant seventh, the only kind the substitution works for
+pub fn tritone_sub_line(chord: &Chord) -> Option<String> {
+    if chord.kind != ChordKind::Dominant7 {
+        return None;
+    }
+    let substitute = Chord {
+        root: tritone_sub(chord.root),
+        kind: chord.kind,
+        bass: chord.bass.map(tritone_sub),
+    };
+    let spell = |notes: &[Note]| notes.iter().map(|note| note.flat_name()).join(", ");
+    let notes = substitute.notes();
+    let shared = notes
+        .iter()
+        .filter(|note| chord.notes().contains(note))
+        .copied()
+        .collect::<Vec<_>>();
+    let name = substitute.to_string().replacen(
+        &substitute.root.to_string(),
+        substitute.root.flat_name(),
+        1,
+    );
+    Some(format!(
+        "{chord} -> {name} ({}), sharing {}",
+        spell(&notes),
+        spell(&shared)
+    ))
+}
+
+/// modes from the most to the least often improvised over chords, dorian first as the usual
+/// choice over minor sevenths
+const COMMON_MODES: [ScaleMode; ScaleMode::COUNT] = [
+    ScaleMode::Major,
+    ScaleMode::Dorian,
+    ScaleMode::Minor,
+    ScaleMode::Mixolydian,
+    ScaleMode::Lydian,
+    ScaleMode::Phrygian,
+    ScaleMode::Locrian,
+    ScaleMode::MelodicMinor,
+    ScaleMode::HarmonicMajor,
+    ScaleMode::PhrygianDominant,
+    ScaleMode::HungarianMinor,
+    ScaleMode::NeapolitanMinor,
+    ScaleMode::BebopDominant,
+    ScaleMode::BebopMajor,
+];
+
+/// scales containing every tone of `chord`: the ones rooted on the chord root first, then
+/// the more common modes, then roots closer above the chord root
+pub fn scales_over(chord: &Chord) -> Vec<Scale> {
+    let rank = |scale: &Scale| {
+        (
+            scale.start_note != chord.root,
+            COMMON_MODES.iter().position(|mode| *mode == scale.mode),
+            chord.root.ascending_distance(scale.start_note),
+        )
+    };
+    scales_containing(chord.notes())
+        .into_iter()
+        .sorted_by_key(rank)
+        .collect()
+}
+
+/// scales rooted on the chord root whose tonic chord, stacked in thirds, is this chord: the
+/// modes it can be the I chord of, the more common ones first
+pub fn modes_for(chord: &Chord) -> Vec<Scale> {
+    let mask = note_bitmask(chord.notes());
+    let Some(size) = ChordSize::value_variants()
+        .iter()
+        .find(|size| size.note_count() == mask.count_ones() as usize)
+    else {
+        return Vec::new();
+    };
+    COMMON_MODES
+        .iter()
+        .map(|mode| Scale {
+            start_note: chord.root,
+            mode: *mode,
+        })
+        .filter(|scale| note_bitmask(scale.chord(1, *size)) == mask)
+        .collect()
+}
+
+/// scales containing every chord tone, keeping only the ones with the fewest notes
+pub fn fit_scales(chords: &[Chord]) -> Vec<Scale> {
+    let candidates = scales_containing(chords.iter().flat_map(Chord::notes));
+    let smallest = candidates
+        .iter()
+        .map(|scale| scale.bitmask().count_ones())
+        .min();
+    candidates
+        .into_iter()
+        .filter(|scale| Some(scale.bitmask().count_ones()) == smallest)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scale::ScaleMode;
+
+    #[test]
+    fn test_parse_chord() {
+        use Note::*;
+        let chord = |s: &str| s.parse::<Chord>().unwrap().notes();
+        assert_eq!(chord("C"), vec![C, E, G]);
+        assert_eq!(chord("Am7"), vec![A, C, E, G]);
+        assert_eq!(chord("Cmaj7"), vec![C, E, G, B]);
+        assert_eq!(chord("Bbm7b5"), vec![As, Cs, E, Gs]);
+        assert_eq!("F#dim".parse::<Chord>().unwrap().to_string(), "F#dim");
+        assert!("Cfoo".parse::<Chord>().is_err());
+        assert!("".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn test_parse_slash_chord() {
+        use Note::*;
+        let c_over_e: Chord = "C/E".parse().unwrap();
+        assert_eq!(c_over_e.root, C);
+        assert_eq!(c_over_e.bass, Some(E));
+        assert_eq!(c_over_e.notes(), vec![C, E, G]);
+        assert_eq!(c_over_e.inversion(), Some(1));
+        assert_eq!(c_over_e.to_string(), "C/E");
+        let a_minor_over_c: Chord = "Am/C".parse().unwrap();
+        assert_eq!(a_minor_over_c.kind, ChordKind::Minor);
+        assert_eq!(a_minor_over_c.bass(), C);
+        assert_eq!(a_minor_over_c.inversion(), Some(1));
+        assert_eq!("G7/F".parse::<Chord>().unwrap().inversion(), Some(3));
+        assert_eq!("C/D".parse::<Chord>().unwrap().inversion(), None);
+        assert_eq!("Am".parse::<Chord>().unwrap().inversion(), Some(0));
+        assert!("C/".parse::<Chord>().is_err());
+        assert!("C/H".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn test_roman_numerals() {
+        assert_eq!(roman_numeral(4), "IV");
+        assert_eq!(parse_roman_numeral("ii"), Some(2));
+        assert_eq!(parse_roman_numeral("VII"), Some(7));
+        assert_eq!(parse_roman_numeral("VIII"), None);
+        assert_eq!("I".parse(), Ok(TriadOverlay::Degrees(vec![1])));
+        assert_eq!("IV, ii".parse(), Ok(TriadOverlay::Degrees(vec![4, 2])));
+        assert!("I,".parse::<TriadOverlay>().is_err());
+        assert_eq!("All".parse(), Ok(TriadOverlay::All));
+        assert!("X".parse::<TriadOverlay>().is_err());
+    }
+
+    #[test]
+    fn test_chord_quality() {
+        use Note::*;
+        assert_eq!(
+            ChordQuality::of_triad(&[C, E, G]),
+            Some(ChordQuality::Major)
+        );
+        assert_eq!(
+            ChordQuality::of_triad(&[B, D, F]),
+            Some(ChordQuality::Diminished)
+        );
+        assert_eq!(
+            ChordQuality::of_triad(&[C, E, Gs]),
+            Some(ChordQuality::Augmented)
+        );
+        assert_eq!(ChordQuality::of_triad(&[C, D, G]), None);
+        assert_eq!(ChordQuality::Diminished.numeral(7), "vii°");
+        assert_eq!(ChordQuality::Augmented.numeral(3), "III+");
+    }
+
+    #[test]
+    fn test_fit_scale_ii_v_i() {
+        let progression = ["Dm7", "G7", "Cmaj7"]
+            .iter()
+            .map(|chord| chord.parse().unwrap())
+            .collect::<Vec<Chord>>();
+        let fitting = fit_scales(&progression);
+        assert!(fitting
+            .iter()
+            .any(|scale| scale.start_note == Note::C && matches!(scale.mode, ScaleMode::Major)));
+        assert!(fitting
+            .iter()
+            .all(|scale| scale.bitmask().count_ones() == 7));
+    }
+
+    #[test]
+    fn test_inversions() {
+        use Note::*;
+        let c_major: Chord = "C".parse().unwrap();
+        let first = c_major.inverted(1).unwrap();
+        assert_eq!(first.to_string(), "C/E");
+        assert_eq!(first.voiced_notes(), vec![E, G, C]);
+        assert_eq!(
+            first.pitches(4),
+            vec![Pitch::new(E, 4), Pitch::new(G, 4), Pitch::new(C, 5)]
+        );
+        assert_eq!(c_major.inverted(2).unwrap().voiced_notes(), vec![G, C, E]);
+        assert_eq!(c_major.inverted(0), Some(c_major));
+        assert_eq!(c_major.inverted(3), None);
+        let g7: Chord = "G7".parse().unwrap();
+        assert_eq!(g7.inverted(3).unwrap().voiced_notes(), vec![F, G, B, D]);
+        let c_over_d: Chord = "C/D".parse().unwrap();
+        assert_eq!(c_over_d.voiced_notes(), vec![D, C, E, G]);
+    }
+
+    #[test]
+    fn test_available_tensions() {
+        use Note::*;
+        let c_major = Scale {
+            start_note: C,
+            mode: ScaleMode::Major,
+        };
+        let cmaj7: Chord = "Cmaj7".parse().unwrap();
+        assert_eq!(
+            cmaj7.available_tensions(&c_major),
+            vec![("9", D), ("13", A)]
+        );
+        let g7: Chord = "G7".parse().unwrap();
+        assert_eq!(g7.available_tensions(&c_major), vec![("9", A), ("13", E)]);
+        let dm7: Chord = "Dm7".parse().unwrap();
+        assert_eq!(
+            dm7.available_tensions(&c_major),
+            vec![("9", E), ("11", G), ("13", B)]
+        );
+        let phrygian_dominant = Scale {
+            start_note: E,
+            mode: ScaleMode::PhrygianDominant,
+        };
+        let e7: Chord = "E7".parse().unwrap();
+        assert_eq!(
+            e7.available_tensions(&phrygian_dominant),
+            vec![("b9", F), ("b13", C)]
+        );
+    }
+
+    #[test]
+    fn test_tritone_sub() {
+        assert_eq!(tritone_sub(Note::G), Note::Cs);
+        let g7: Chord = "G7".parse().unwrap();
+        let db7 = Chord {
+            root: tritone_sub(g7.root),
+            ..g7
+        };
+        let (g7_notes, db7_notes) = (g7.notes(), db7.notes());
+        assert!(db7_notes.contains(&g7_notes[1]) && db7_notes.contains(&g7_notes[3]));
+        assert_eq!(
+            tritone_sub_line(&g7).as_deref(),
+            Some("G7 -> Db7 (Db, F, Ab, B), sharing F, B")
+        );
+        assert_eq!(tritone_sub_line(&"Gmaj7".parse().unwrap()), None);
+        assert_eq!(tritone_sub_line(&"Dm7".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn test_scales_over() {
+        let scale = |s: &str| s.parse::<Scale>().unwrap();
+        let scales = scales_over(&"Dm7".parse().unwrap());
+        assert_eq!(
+            scales[..3],
+            [scale("D dorian"), scale("D minor"), scale("D phrygian")]
+        );
+        assert!(scales.contains(&scale("F major")));
+        let first_other_root = scales
+            .iter()
+            .find(|scale| scale.start_note != Note::D)
+            .unwrap();
+        assert_eq!(*first_other_root, scale("F major"));
+        assert_eq!(
+            scales_over(&"G7".parse().unwrap())[0],
+            scale("G mixolydian")
+        );
+    }
+
+    #[test]
+    fn test_chord_from_notes() {
+        use Note::*;
+        let set: NoteSet = "C,E,G,Fb".parse().unwrap();
+        assert_eq!(
+            Chord::from_notes(&set.simplified()),
+            Some("C".parse().unwrap())
+        );
+        assert_eq!(set.simplified().len(), 3);
+        assert_eq!(Chord::from_notes(&[C, E, G, E]), None);
+        assert_eq!(
+            Chord::from_notes(&[A, C, E, G]),
+            Some("Am7".parse().unwrap())
+        );
+        assert_eq!(Chord::from_notes(&[C, D]), None);
+        assert_eq!(
+            "Am7".parse::<ChordInput>(),
+            Ok(ChordInput::Symbol("Am7".parse().unwrap()))
+        );
+        assert!(matches!("A,C,E".parse(), Ok(ChordInput::Notes(_))));
+        assert!("A,C,X".parse::<ChordInput>().is_err());
+        let Ok(ChordInput::Notes(set)) = ChordInput::parse_with("H,D,Fis", NoteInput::German)
+        else {
+            panic!("H,D,Fis is a German note list");
+        };
+        let notes = set
+            .entries
+            .iter()
+            .map(|(_, note)| *note)
+            .collect::<Vec<_>>();
+        assert_eq!(notes, vec![B, D, Fs]);
+    }
+
+    #[test]
+    fn test_modes_for() {
+        let modes = |s: &str| {
+            modes_for(&s.parse().unwrap())
+                .into_iter()
+                .map(|scale| scale.mode)
+                .collect::<Vec<_>>()
+        };
+        let minor_seventh = modes("Dm7");
+        assert!(minor_seventh.contains(&ScaleMode::Dorian));
+        assert!(minor_seventh.contains(&ScaleMode::Minor));
+        assert!(!minor_seventh.contains(&ScaleMode::Major));
+        assert_eq!(
+            modes("G7"),
+            [
+                ScaleMode::Mixolydian,
+                ScaleMode::PhrygianDominant,
+                ScaleMode::BebopDominant
+            ]
+        );
+        assert!(modes("Cmaj7").starts_with(&[ScaleMode::Major, ScaleMode::Lydian]));
+        assert!(modes("Cmaj7").contains(&ScaleMode::BebopMajor));
+    }
+}
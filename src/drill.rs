@@ -0,0 +1,195 @@
+use crate::guitar::Guitar;
+use crate::interval::Interval;
+use crate::note::Note;
+use clap::ValueEnum;
+use rand::rngs::StdRng;
+use rand::RngExt;
+use std::ops::Range;
+use strum::EnumCount;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Drill {
+    NoteFinder,
+    Interval,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotePrompt {
+    /// string number as printed on the fretboard, 1 being the lowest
+    pub string: usize,
+    pub note: Note,
+}
+
+pub fn note_finder_prompts(guitar: &Guitar, count: usize, rng: &mut StdRng) -> Vec<NotePrompt> {
+    if guitar.strings.is_empty() {
+        return vec![];
+    }
+    (0..count)
+        .map(|_| NotePrompt {
+            string: rng.random_range(0..guitar.strings.len()) + 1,
+            note: Note::from_index(rng.random_range(0..Note::COUNT)),
+        })
+        .collect()
+}
+
+pub fn note_finder_answer(guitar: &Guitar, prompt: &NotePrompt, frets: Range<usize>) -> Vec<usize> {
+    guitar.strings[prompt.string - 1].frets_of(prompt.note, frets)
+}
+
+pub fn render_note_finder(
+    guitar: &Guitar,
+    prompts: &[NotePrompt],
+    frets: Range<usize>,
+    show_answers: bool,
+) -> String {
+    prompts
+        .iter()
+        .enumerate()
+        .map(|(num, prompt)| {
+            let question = format!(
+                "{}. string {}({}): find {}",
+                num + 1,
+                prompt.string,
+                guitar.strings[prompt.string - 1].start,
+                prompt.note
+            );
+            match show_answers {
+                true => {
+                    let answer = note_finder_answer(guitar, prompt, frets.clone());
+                    let answer = answer
+                        .iter()
+                        .map(|fret| fret.to_string())
+                        .collect::<Vec<_>>();
+                    format!("{question}\t-> fret {}\n", answer.join(", "))
+                }
+                false => format!("{question}\n"),
+            }
+        })
+        .collect()
+}
+
+/// a fretted position: zero-based string index (lowest string first) and fret
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub string: usize,
+    pub fret: usize,
+}
+
+pub fn interval_prompts(
+    guitar: &Guitar,
+    count: usize,
+    frets: Range<usize>,
+    rng: &mut StdRng,
+) -> Vec<(Position, Position)> {
+    if frets.is_empty() || guitar.strings.is_empty() {
+        return vec![];
+    }
+    let mut position = || Position {
+        string: rng.random_range(0..guitar.strings.len()),
+        fret: rng.random_range(frets.clone()),
+    };
+    (0..count).map(|_| (position(), position())).collect()
+}
+
+pub fn interval_answer(guitar: &Guitar, (a, b): (Position, Position)) -> (Interval, usize) {
+    Interval::between(
+        guitar.pitch_at(a.string, a.fret),
+        guitar.pitch_at(b.string, b.fret),
+    )
+}
+
+pub fn render_interval_drill(
+    guitar: &Guitar,
+    prompts: &[(Position, Position)],
+    show_answers: bool,
+) -> String {
+    prompts
+        .iter()
+        .enumerate()
+        .map(|(num, (a, b))| {
+            let question = format!(
+                "{}. string {} fret {} -> string {} fret {}",
+                num + 1,
+                a.string + 1,
+                a.fret,
+                b.string + 1,
+                b.fret
+            );
+            match (show_answers, interval_answer(guitar, (*a, *b))) {
+                (false, _) => format!("{question}\n"),
+                (true, (interval, 0)) => format!("{question}\t= {interval}\n"),
+                (true, (interval, octaves)) => {
+                    format!("{question}\t= {interval} (+{octaves} octave)\n")
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guitar::Tuning;
+    use crate::random::rng_from_seed;
+
+    #[test]
+    fn test_seeded_prompts_are_deterministic() {
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Fourths);
+        let first = note_finder_prompts(&guitar, 20, &mut rng_from_seed(Some(7)));
+        let second = note_finder_prompts(&guitar, 20, &mut rng_from_seed(Some(7)));
+        assert_eq!(first.len(), 20);
+        assert_eq!(first, second);
+        assert_ne!(
+            first,
+            note_finder_prompts(&guitar, 20, &mut rng_from_seed(Some(8)))
+        );
+        assert!(first.iter().all(|prompt| (1..=6).contains(&prompt.string)));
+    }
+
+    #[test]
+    fn test_note_finder_answer() {
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Fourths);
+        let prompt = NotePrompt {
+            string: 2,
+            note: Note::C,
+        };
+        assert_eq!(note_finder_answer(&guitar, &prompt, 0..24), vec![3, 15]);
+        let rendered = render_note_finder(&guitar, &[prompt], 0..13, true);
+        assert_eq!(rendered, "1. string 2(A): find C\t-> fret 3\n");
+    }
+
+    #[test]
+    fn test_interval_drill() {
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Fourths);
+        let open_e = Position { string: 0, fret: 0 };
+        let open_a = Position { string: 1, fret: 0 };
+        assert_eq!(
+            interval_answer(&guitar, (open_e, open_a)),
+            (Interval::PerfectFourth, 0)
+        );
+        let rendered = render_interval_drill(&guitar, &[(open_e, open_a)], true);
+        assert_eq!(
+            rendered,
+            "1. string 1 fret 0 -> string 2 fret 0\t= perfect fourth\n"
+        );
+        let first = interval_prompts(&guitar, 5, 0..12, &mut rng_from_seed(Some(1)));
+        let second = interval_prompts(&guitar, 5, 0..12, &mut rng_from_seed(Some(1)));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_empty_neck_has_no_interval_prompts() {
+        let guitar = Guitar::from_tuning(6, Note::E, 0, Tuning::Fourths);
+        let prompts = interval_prompts(&guitar, 5, 0..0, &mut rng_from_seed(Some(1)));
+        assert!(prompts.is_empty());
+        assert_eq!(render_interval_drill(&guitar, &prompts, true), "");
+    }
+
+    #[test]
+    fn test_stringless_neck_has_no_note_prompts() {
+        let guitar = Guitar::from_tuning(0, Note::E, 24, Tuning::Fourths);
+        let prompts = note_finder_prompts(&guitar, 5, &mut rng_from_seed(Some(1)));
+        assert!(prompts.is_empty());
+        assert_eq!(render_note_finder(&guitar, &prompts, 0..24, true), "");
+    }
+}
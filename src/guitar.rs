@@ -0,0 +1,1073 @@
+use crate::chord::ChordSize;
+use crate::note::{Note, NoteInput};
+use crate::pitch::{OctaveNotation, Pitch};
+use crate::scale::Scale;
+use derive_more::{Display, Error};
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::str::FromStr;
+use strum::EnumCount;
+
+/// which pitches a string has frets for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fretting {
+    /// a fret on every semitone
+    Chromatic,
+    /// one fret per scale step, the semitone steps repeating every octave
+    Diatonic(Vec<usize>),
+}
+
+#[derive(Debug)]
+pub struct GuitarString {
+    pub start: Note,
+    pub fretting: Fretting,
+}
+
+impl GuitarString {
+    pub fn new(start: Note) -> Self {
+        Self {
+            start,
+            fretting: Fretting::Chromatic,
+        }
+    }
+
+    pub fn diatonic(start: Note, steps: Vec<usize>) -> Self {
+        Self {
+            start,
+            fretting: Fretting::Diatonic(steps),
+        }
+    }
+
+    /// semitones the fret sounds above the open string
+    pub fn semitones_at(&self, fret: usize) -> usize {
+        match &self.fretting {
+            Fretting::Chromatic => fret,
+            Fretting::Diatonic(steps) if steps.is_empty() => fret,
+            Fretting::Diatonic(steps) => steps.iter().cycle().take(fret).sum(),
+        }
+    }
+
+    pub fn note_at(&self, fret: usize) -> Note {
+        self.start.offset_by(self.semitones_at(fret) as _)
+    }
+
+    pub fn frets_of(&self, note: Note, frets: std::ops::Range<usize>) -> Vec<usize> {
+        frets.filter(|fret| self.note_at(*fret) == note).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Guitar {
+    pub strings: Vec<GuitarString>,
+    pub notes_per_string: usize,
+    pub lowest_octave: i32,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Tuning {
+    Standard,
+    Fourths,
+    /// every string a perfect fifth above the one below, like a cello
+    Fifths,
+    ScaleCentered,
+    /// three string mountain dulcimer in D A A with mixolydian diatonic frets
+    Dulcimer,
+    /// open strings given note by note, lowest first
+    Custom(Vec<Note>),
+}
+
+impl Tuning {
+    /// every tuning that is not [Tuning::Custom]
+    pub fn presets() -> Vec<Self> {
+        vec![
+            Tuning::Standard,
+            Tuning::Fourths,
+            Tuning::Fifths,
+            Tuning::ScaleCentered,
+            Tuning::Dulcimer,
+        ]
+    }
+
+    /// the command line name, `None` for a custom tuning
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Tuning::Standard => Some("standard"),
+            Tuning::Fourths => Some("fourths"),
+            Tuning::Fifths => Some("fifths"),
+            Tuning::ScaleCentered => Some("scale-centered"),
+            Tuning::Dulcimer => Some("dulcimer"),
+            Tuning::Custom(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Tuning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            Tuning::Standard => "Standard",
+            Tuning::Fourths => "All fourths",
+            Tuning::Fifths => "All fifths",
+            Tuning::ScaleCentered => "Scale centered",
+            Tuning::Dulcimer => "Dulcimer",
+            Tuning::Custom(notes) => {
+                let notes = notes
+                    .iter()
+                    .map(|note| note.to_string())
+                    .collect::<Vec<_>>();
+                return write!(f, "Custom {}", notes.join(" "));
+            }
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(
+    fmt = "invalid tuning `{}`, expected {} or comma separated notes",
+    input,
+    "preset_names()"
+)]
+pub struct ParseTuningError {
+    #[error(not(source))]
+    pub input: String,
+}
+
+/// the names [Tuning::from_str] accepts for the presets, comma separated
+fn preset_names() -> String {
+    Tuning::presets()
+        .iter()
+        .filter_map(Tuning::name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// a string set [Guitar::find_voicing_on] cannot play on
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+pub enum StringSetError {
+    #[display(fmt = "string {} is not between 1 and {}", string, count)]
+    OutOfRange { string: usize, count: usize },
+    #[display(fmt = "string {} is listed more than once", string)]
+    Duplicate { string: usize },
+}
+
+/// a melody note [Guitar::transcribe] finds on no string
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(
+    fmt = "note {} of the melody, {}, is on no string up to fret {}",
+    "index + 1",
+    note,
+    max_fret
+)]
+pub struct UnreachableNoteError {
+    /// zero-based position in the melody
+    pub index: usize,
+    pub note: Note,
+    pub max_fret: usize,
+}
+
+impl FromStr for Tuning {
+    type Err = ParseTuningError;
+
+    /// a preset name such as `fourths`, or the open strings like `D,A,D,G,A,D`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, NoteInput::English)
+    }
+}
+
+impl Tuning {
+    /// like [Tuning::from_str] with the open strings written in the `notes` convention
+    pub fn parse_with(s: &str, notes: NoteInput) -> Result<Self, ParseTuningError> {
+        let error = || ParseTuningError {
+            input: s.to_string(),
+        };
+        if let Some(preset) = Self::presets().into_iter().find(|preset| {
+            preset
+                .name()
+                .is_some_and(|name| name.eq_ignore_ascii_case(s.trim()))
+        }) {
+            return Ok(preset);
+        }
+        s.split(',')
+            .map(|name| notes.parse(name))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Tuning::Custom)
+            .map_err(|_| error())
+    }
+}
+
+/// every tuning with its open strings on a six string guitar starting from E, lowest first
+pub fn list_tunings() -> String {
+    Tuning::presets()
+        .into_iter()
+        .map(|tuning| {
+            let guitar = Guitar::from_tuning(6, Note::E, 0, tuning.clone());
+            let open = guitar
+                .strings
+                .iter()
+                .map(|string| string.start.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{tuning}\t{open}\n")
+        })
+        .collect()
+}
+
+pub const LOWEST_STRING_OCTAVE: i32 = 2;
+
+/// how much each kind of difficulty counts in [voicing_score]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoicingWeights {
+    /// per fret between the lowest and the highest fretted note
+    pub stretch: usize,
+    /// per muted string
+    pub muted: usize,
+    /// once when the fingering needs more than four fingers, so a barre
+    pub barre: usize,
+    /// per fret between the nut and the lowest fretted note
+    pub position: usize,
+}
+
+impl Default for VoicingWeights {
+    fn default() -> Self {
+        Self {
+            stretch: 2,
+            muted: 1,
+            barre: 3,
+            position: 1,
+        }
+    }
+}
+
+/// how hard a fingering is to play, lower is easier; open strings cost nothing
+pub fn voicing_score(voicing: &[Option<usize>], weights: VoicingWeights) -> usize {
+    let fretted = voicing
+        .iter()
+        .flatten()
+        .filter(|fret| **fret > 0)
+        .collect::<Vec<_>>();
+    let (stretch, position) = match (fretted.iter().min(), fretted.iter().max()) {
+        (Some(low), Some(high)) => (**high - **low, **low),
+        _ => (0, 0),
+    };
+    let muted = voicing.iter().filter(|fret| fret.is_none()).count();
+    let barre = match fretted.len() > 4 {
+        true => weights.barre,
+        false => 0,
+    };
+    stretch * weights.stretch + muted * weights.muted + barre + position * weights.position
+}
+
+/// `x32010`, or `x-3-2-0-1-0` once any fret has two digits
+pub fn voicing_name(voicing: &[Option<usize>]) -> String {
+    let separator = match voicing.iter().flatten().any(|fret| *fret > 9) {
+        true => "-",
+        false => "",
+    };
+    voicing
+        .iter()
+        .map(|fret| fret.map_or("x".to_string(), |fret| fret.to_string()))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// semitone steps of the minor pentatonic scale
+pub const MINOR_PENTATONIC_STEPS: [usize; 5] = [3, 2, 2, 3, 2];
+
+/// semitone steps between the frets of a dulcimer, a mixolydian scale from the open string
+pub const DULCIMER_STEPS: [usize; 7] = [2, 2, 1, 2, 2, 1, 2];
+
+/// open, 5th and 12th fret frequencies of every string, highest string first like the neck,
+/// the octave numbers counted from `boundary`
+pub fn verify_tuning(guitar: &Guitar, notation: OctaveNotation, boundary: Note) -> String {
+    (0..guitar.strings.len())
+        .rev()
+        .map(|string| {
+            let frets = [0, 5, 12].map(|fret| {
+                let pitch = guitar.pitch_at(string, fret);
+                format!(
+                    "{fret}: {} {:.2} Hz",
+                    pitch.name_from(notation, boundary),
+                    pitch.frequency()
+                )
+            });
+            format!("{}\t{}\n", string + 1, frets.join("\t"))
+        })
+        .collect()
+}
+
+impl Guitar {
+    /// open string pitches, lowest string first, each string sounding above the previous one
+    pub fn open_pitches(&self) -> Vec<Pitch> {
+        let mut pitches: Vec<Pitch> = vec![];
+        for string in &self.strings {
+            let pitch = match pitches.last() {
+                Some(last) => last.offset_by(last.note.ascending_distance(string.start) as _),
+                None => Pitch::new(string.start, self.lowest_octave),
+            };
+            pitches.push(pitch);
+        }
+        pitches
+    }
+
+    /// `string` is the zero-based index, lowest string first
+    pub fn pitch_at(&self, string: usize, fret: usize) -> Pitch {
+        self.open_pitches()[string].offset_by(self.strings[string].semitones_at(fret) as _)
+    }
+
+    /// every (zero-based string index, fret) where `note` is played, up to `max_fret` inclusive
+    pub fn positions_of(&self, note: Note, max_fret: usize) -> Vec<(usize, usize)> {
+        self.strings
+            .iter()
+            .enumerate()
+            .flat_map(|(index, string)| {
+                string
+                    .frets_of(note, 0..max_fret + 1)
+                    .into_iter()
+                    .map(move |fret| (index, fret))
+            })
+            .collect()
+    }
+
+    /// a (zero-based string, fret) position up to `max_fret` for every note of `melody`:
+    /// the lowest pitch for the first note, then each note at the pitch nearest to the
+    /// previous one. The hand covers four frets and only moves when a note is out of its
+    /// reach (open strings always are in reach); ties go to the lower fret. Fails on the
+    /// first note no string has up to `max_fret`
+    pub fn transcribe(
+        &self,
+        melody: &[Note],
+        max_fret: usize,
+    ) -> Result<Vec<(usize, usize)>, UnreachableNoteError> {
+        const HAND_SPAN: usize = 4;
+        let reach = |hand: usize, fret: usize| match fret {
+            0 => 0,
+            _ if fret < hand => hand - fret,
+            _ => fret.saturating_sub(hand + HAND_SPAN - 1),
+        };
+        let mut previous: Option<(Pitch, usize)> = None;
+        let mut positions = Vec::new();
+        for (index, note) in melody.iter().copied().enumerate() {
+            let candidates = self.positions_of(note, max_fret);
+            let best = match previous {
+                None => candidates
+                    .into_iter()
+                    .min_by_key(|(string, fret)| (self.pitch_at(*string, *fret), *fret)),
+                Some((pitch, hand)) => candidates.into_iter().min_by_key(|(string, fret)| {
+                    let leap = self.pitch_at(*string, *fret).semitones() - pitch.semitones();
+                    (leap.abs(), leap < 0, reach(hand, *fret), *fret)
+                }),
+            };
+            let (string, fret) = best.ok_or(UnreachableNoteError {
+                index,
+                note,
+                max_fret,
+            })?;
+            let hand = match previous {
+                None => fret,
+                Some((_, hand)) if fret == 0 || fret >= hand => {
+                    hand.max(fret.saturating_sub(HAND_SPAN - 1))
+                }
+                Some(_) => fret,
+            };
+            positions.push((string, fret));
+            previous = Some((self.pitch_at(string, fret), hand));
+        }
+        Ok(positions)
+    }
+
+    /// first fret producing `note` on the lowest string
+    pub fn lowest_string_fret_of(&self, note: Note) -> Option<usize> {
+        self.strings
+            .first()?
+            .frets_of(note, 0..Note::COUNT)
+            .first()
+            .copied()
+    }
+
+    /// a fret (or `None` for a muted string) per string, lowest first, sounding every note of
+    /// `chord` with its first note in the bass; fretted notes fit in `max_stretch` frets and
+    /// only the lowest strings are muted. The lowest position wins, then fewer muted strings
+    pub fn find_voicing(&self, chord: &[Note], max_stretch: usize) -> Option<Vec<Option<usize>>> {
+        let strings = (1..=self.strings.len()).collect::<Vec<_>>();
+        self.voicing_on(chord, max_stretch, &strings)
+    }
+
+    /// [Guitar::find_voicing] played on the given strings only (numbered from 1, lowest
+    /// first), every other string muted
+    pub fn find_voicing_on(
+        &self,
+        chord: &[Note],
+        max_stretch: usize,
+        strings: &[usize],
+    ) -> Result<Option<Vec<Option<usize>>>, StringSetError> {
+        let count = self.strings.len();
+        if let Some(string) = strings.iter().find(|string| !(1..=count).contains(*string)) {
+            return Err(StringSetError::OutOfRange {
+                string: *string,
+                count,
+            });
+        }
+        if let Some(string) = strings.iter().duplicates().next() {
+            return Err(StringSetError::Duplicate { string: *string });
+        }
+        Ok(self.voicing_on(chord, max_stretch, strings))
+    }
+
+    fn voicing_on(
+        &self,
+        chord: &[Note],
+        max_stretch: usize,
+        strings: &[usize],
+    ) -> Option<Vec<Option<usize>>> {
+        chord.first()?;
+        (1..self.notes_per_string.max(2)).find_map(|start| {
+            self.voicings_at(chord, start, max_stretch, strings)
+                .min_by_key(|voicing| {
+                    let muted = voicing.iter().filter(|fret| fret.is_none()).count();
+                    (muted, voicing.iter().flatten().sum::<usize>())
+                })
+        })
+    }
+
+    /// the easiest fingering of `chord` by [voicing_score] anywhere on the neck, with the same
+    /// rules as [Guitar::find_voicing]
+    pub fn best_voicing(
+        &self,
+        chord: &[Note],
+        max_stretch: usize,
+        weights: VoicingWeights,
+    ) -> Option<Vec<Option<usize>>> {
+        chord.first()?;
+        let strings = (1..=self.strings.len()).collect::<Vec<_>>();
+        (1..self.notes_per_string.max(2))
+            .flat_map(|start| {
+                self.voicings_at(chord, start, max_stretch, &strings)
+                    .collect_vec()
+            })
+            .min_by_key(|voicing| {
+                (
+                    voicing_score(voicing, weights),
+                    voicing.iter().flatten().sum::<usize>(),
+                )
+            })
+    }
+
+    /// every playable fingering of `chord` on `strings` with its fretted notes between `start`
+    /// and `start + max_stretch`
+    fn voicings_at<'a>(
+        &'a self,
+        chord: &'a [Note],
+        start: usize,
+        max_stretch: usize,
+        strings: &'a [usize],
+    ) -> impl Iterator<Item = Vec<Option<usize>>> + 'a {
+        let frets = (start..(start + max_stretch).min(self.notes_per_string)).chain([0]);
+        self.strings
+            .iter()
+            .enumerate()
+            .map(|(index, string)| match strings.contains(&(index + 1)) {
+                true => frets
+                    .clone()
+                    .filter(|fret| chord.contains(&string.note_at(*fret)))
+                    .map(Some)
+                    .chain([None])
+                    .collect::<Vec<_>>(),
+                false => vec![None],
+            })
+            .multi_cartesian_product()
+            .filter(move |voicing| {
+                let sounding = voicing
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| strings.contains(&(index + 1)))
+                    .map(|(_, fret)| fret)
+                    .skip_while(|fret| fret.is_none());
+                let notes = voicing
+                    .iter()
+                    .zip(&self.strings)
+                    .filter_map(|(fret, string)| fret.map(|fret| string.note_at(fret)))
+                    .collect::<Vec<_>>();
+                sounding.clone().all(Option::is_some)
+                    && notes.first() == chord.first()
+                    && chord.iter().all(|note| notes.contains(note))
+            })
+    }
+
+    /// whether `span` frets from `start` reach a tone of the scale's tonic triad on every string
+    fn grabs_tonic(&self, scale: &Scale, start: usize, span: usize) -> bool {
+        let triad = scale.chord(1, ChordSize::Triad);
+        self.strings
+            .iter()
+            .all(|string| (start..start + span).any(|fret| triad.contains(&string.note_at(fret))))
+    }
+
+    /// closed shapes of the scale within an octave of frets, each one the run of window start
+    /// frets where a `span` fret hand grabs the tonic triad on every string; the shapes are
+    /// told apart by the positions where it cannot, so in standard tuning a major scale has
+    /// the five CAGED shapes
+    pub fn shape_windows(&self, scale: &Scale, span: usize) -> Vec<Vec<usize>> {
+        let octave = Note::COUNT;
+        let fits = (0..octave)
+            .map(|start| self.grabs_tonic(scale, start, span))
+            .collect::<Vec<_>>();
+        let Some(gap) = fits.iter().position(|fits| !fits) else {
+            return match self.strings.is_empty() {
+                true => vec![],
+                false => vec![(0..octave).collect()],
+            };
+        };
+        let mut shapes: Vec<Vec<usize>> = vec![];
+        let mut current = vec![];
+        for start in (gap + 1..gap + 1 + octave).map(|fret| fret % octave) {
+            match fits[start] {
+                true => current.push(start),
+                false if !current.is_empty() => shapes.push(std::mem::take(&mut current)),
+                false => {}
+            }
+        }
+        shapes.sort();
+        shapes
+    }
+
+    /// (string index, fret) positions of the `number`th (1-based) minor pentatonic box of
+    /// `root`: two notes per string, box 1 starting on the lowest root of the lowest string
+    /// and every later box starting on the next pentatonic note
+    pub fn pentatonic_box(&self, root: Note, number: usize) -> Vec<(usize, usize)> {
+        let Some(first) = self.lowest_string_fret_of(root) else {
+            return vec![];
+        };
+        let open = self.open_pitches();
+        let string_offsets = open
+            .iter()
+            .map(|pitch| pitch.semitones() - open[0].semitones())
+            .collect::<Vec<_>>();
+        let pitches = MINOR_PENTATONIC_STEPS
+            .iter()
+            .cycle()
+            .scan(first as i32, |pitch, step| {
+                let current = *pitch;
+                *pitch += *step as i32;
+                Some(current)
+            })
+            .skip(number.saturating_sub(1) % MINOR_PENTATONIC_STEPS.len())
+            .take(string_offsets.len() * 2)
+            .collect::<Vec<_>>();
+        let positions = pitches
+            .chunks(2)
+            .zip(&string_offsets)
+            .enumerate()
+            .flat_map(|(string, (pair, offset))| {
+                pair.iter().map(move |pitch| (string, pitch - offset))
+            })
+            .collect::<Vec<_>>();
+        let shift = match positions.iter().any(|(_, fret)| *fret < 0) {
+            true => Note::COUNT as i32,
+            false => 0,
+        };
+        positions
+            .into_iter()
+            .map(|(string, fret)| (string, (fret + shift) as usize))
+            .collect()
+    }
+
+    /// lowest fret starting a window of `fret_span` frets that has the root on the lowest
+    /// string and every scale note somewhere across the strings
+    pub fn lowest_playable_window(&self, scale: &Scale, fret_span: usize) -> Option<usize> {
+        let notes = scale.notes();
+        let lowest = self.strings.first()?;
+        (0..self.notes_per_string.saturating_sub(fret_span) + 1).find(|start| {
+            let window = *start..start + fret_span;
+            let reachable = self
+                .strings
+                .iter()
+                .flat_map(|string| window.clone().map(|fret| string.note_at(fret)))
+                .collect::<HashSet<_>>();
+            window
+                .clone()
+                .any(|fret| lowest.note_at(fret) == scale.start_note)
+                && notes.is_subset(&reachable)
+        })
+    }
+
+    pub fn from_tuning(
+        string_count: usize,
+        start: Note,
+        notes_per_string: usize,
+        tuning: Tuning,
+    ) -> Self {
+        let strings = match tuning {
+            Tuning::Standard => {
+                let mut output = vec![start];
+                (1..string_count).for_each(|string| {
+                    let last = *output.last().expect("it is not empty");
+                    let interval = match string_count >= 6 && string == string_count - 2 {
+                        true => 4,
+                        false => 5,
+                    };
+                    output.push(last.offset_by(interval));
+                });
+                output.into_iter().map(GuitarString::new).collect()
+            }
+            Tuning::Fourths => start
+                .cycle_from()
+                .step_by(5)
+                .take(string_count)
+                .map(GuitarString::new)
+                .collect(),
+            Tuning::Fifths => start
+                .cycle_from()
+                .step_by(7)
+                .take(string_count)
+                .map(GuitarString::new)
+                .collect(),
+            Tuning::Custom(notes) => notes.into_iter().map(GuitarString::new).collect(),
+            Tuning::Dulcimer => [Note::D, Note::A, Note::A]
+                .into_iter()
+                .map(|start| GuitarString::diatonic(start, DULCIMER_STEPS.to_vec()))
+                .collect(),
+            Tuning::ScaleCentered => {
+                let intervals: Vec<usize> = vec![4, 4, 4, 4];
+                let mut output = vec![start];
+                intervals
+                    .iter()
+                    .cycle()
+                    .take(string_count.saturating_sub(1))
+                    .for_each(|interval| {
+                        let last = *output.last().expect("it is not empty");
+                        output.push(last.offset_by(*interval as _));
+                    });
+                output
+                    .into_iter()
+                    .take(string_count)
+                    .map(GuitarString::new)
+                    .collect()
+            }
+        };
+        Self {
+            strings,
+            notes_per_string,
+            lowest_octave: LOWEST_STRING_OCTAVE,
+        }
+    }
+
+    /// like [Guitar::from_tuning], but a scale centered neck is laid out for `scale`: the
+    /// strings are a diatonic fourth (three scale steps) apart and the middle string starts
+    /// on the root, so the scale keeps the same shape on every string pair
+    pub fn from_tuning_for_scale(
+        string_count: usize,
+        start: Note,
+        notes_per_string: usize,
+        tuning: Tuning,
+        scale: Option<&Scale>,
+    ) -> Self {
+        let scale = match (&tuning, scale) {
+            (Tuning::ScaleCentered, Some(scale)) => scale,
+            _ => return Self::from_tuning(string_count, start, notes_per_string, tuning),
+        };
+        let degrees = scale.degrees();
+        let middle = (string_count / 2) as i32;
+        let strings = (0..string_count as i32)
+            .map(|string| {
+                let degree = ((string - middle) * 3).rem_euclid(degrees.len() as i32);
+                GuitarString::new(degrees[degree as usize])
+            })
+            .collect();
+        Self {
+            strings,
+            notes_per_string,
+            lowest_octave: LOWEST_STRING_OCTAVE,
+        }
+    }
+
+    /// moves every open string by `semitones`, negative values tune the instrument down;
+    /// frets keep their numbers
+    pub fn with_capo(self, semitones: i32) -> Self {
+        let lowest = self
+            .open_pitches()
+            .first()
+            .map(|pitch| pitch.offset_by(semitones));
+        Self {
+            strings: self
+                .strings
+                .into_iter()
+                .map(|string| GuitarString {
+                    start: string.start.offset_by(semitones),
+                    ..string
+                })
+                .collect(),
+            notes_per_string: self.notes_per_string,
+            lowest_octave: lowest.map_or(self.lowest_octave, |pitch| pitch.octave),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fourths_tuning() {
+        use Note::*;
+        let guitar = Guitar::from_tuning(6, E, 24, Tuning::Fourths);
+        let open = guitar.strings.iter().map(|s| s.start).collect::<Vec<_>>();
+        assert_eq!(open, vec![E, A, D, G, C, F]);
+    }
+
+    #[test]
+    fn test_fifths_tuning() {
+        use Note::*;
+        let guitar = Guitar::from_tuning(5, C, 24, Tuning::Fifths);
+        let open = guitar.strings.iter().map(|s| s.start).collect::<Vec<_>>();
+        assert_eq!(open, vec![C, G, D, A, E]);
+        let open = guitar.open_pitches();
+        assert_eq!(open[0], Pitch::new(C, 2));
+        assert_eq!(open[4], Pitch::new(E, 4));
+        assert_eq!("fifths".parse(), Ok(Tuning::Fifths));
+    }
+
+    #[test]
+    fn test_standard_tuning() {
+        use Note::*;
+        let open = |count| {
+            Guitar::from_tuning(count, E, 24, Tuning::Standard)
+                .strings
+                .iter()
+                .map(|s| s.start)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(open(6), vec![E, A, D, G, B, E]);
+        assert_eq!(open(4), vec![E, A, D, G]);
+    }
+
+    #[test]
+    fn test_lowest_playable_window() {
+        use crate::scale::ScaleMode;
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Standard);
+        let scale = |start_note| Scale {
+            start_note,
+            mode: ScaleMode::Major,
+        };
+        assert_eq!(guitar.lowest_playable_window(&scale(Note::C), 4), Some(5));
+        assert_eq!(guitar.lowest_playable_window(&scale(Note::G), 4), Some(0));
+        assert_eq!(guitar.lowest_playable_window(&scale(Note::C), 1), None);
+    }
+
+    #[test]
+    fn test_list_tunings() {
+        let listing = list_tunings();
+        assert_eq!(listing.lines().count(), Tuning::presets().len());
+        for tuning in Tuning::presets() {
+            assert!(listing.contains(&format!("{tuning}\t")));
+        }
+        assert!(listing.contains("Standard\tE A D G B E\n"));
+    }
+
+    #[test]
+    fn test_negative_capo() {
+        use Note::*;
+        let guitar = Guitar::from_tuning(6, E, 24, Tuning::Standard).with_capo(-1);
+        let open = guitar.strings.iter().map(|s| s.start).collect::<Vec<_>>();
+        // Eb Ab Db Gb Bb Eb
+        assert_eq!(open, vec![Ds, Gs, Cs, Fs, As, Ds]);
+        assert_eq!(guitar.open_pitches()[0], Pitch::new(Ds, 2));
+        let guitar = Guitar::from_tuning(6, E, 24, Tuning::Standard).with_capo(-5);
+        assert_eq!(guitar.open_pitches()[0], Pitch::new(B, 1));
+        let guitar = Guitar::from_tuning(6, E, 24, Tuning::Standard).with_capo(8);
+        assert_eq!(guitar.open_pitches()[0], Pitch::new(C, 3));
+    }
+
+    #[test]
+    fn test_note_at() {
+        let string = GuitarString::new(Note::E);
+        assert_eq!(string.note_at(0), Note::E);
+        assert_eq!(string.note_at(5), Note::A);
+        assert_eq!(string.note_at(13), Note::F);
+        assert_eq!(string.frets_of(Note::G, 0..24), vec![3, 15]);
+    }
+
+    #[test]
+    fn test_open_pitches() {
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Fourths);
+        let open = guitar.open_pitches();
+        assert_eq!(open[0], Pitch::new(Note::E, 2));
+        assert_eq!(open[1], Pitch::new(Note::A, 2));
+        assert_eq!(open[4], Pitch::new(Note::C, 4));
+        assert_eq!(guitar.pitch_at(0, 12), Pitch::new(Note::E, 3));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let custom = Tuning::Custom(vec![Note::D, Note::A, Note::D]);
+        for tuning in Tuning::presets().into_iter().chain([custom]) {
+            let json = serde_json::to_string(&tuning).unwrap();
+            assert_eq!(serde_json::from_str::<Tuning>(&json).unwrap(), tuning);
+        }
+        assert_eq!(
+            serde_json::to_string(&Tuning::ScaleCentered).unwrap(),
+            "\"scale-centered\""
+        );
+    }
+
+    #[test]
+    fn test_positions_of() {
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Standard);
+        assert_eq!(
+            guitar.positions_of(Note::C, 12),
+            vec![(0, 8), (1, 3), (2, 10), (3, 5), (4, 1), (5, 8)]
+        );
+        assert_eq!(guitar.positions_of(Note::E, 0), vec![(0, 0), (5, 0)]);
+    }
+
+    #[test]
+    fn test_verify_tuning() {
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Standard);
+        for string in 0..guitar.strings.len() {
+            assert_eq!(
+                guitar.pitch_at(string, 12).frequency(),
+                guitar.pitch_at(string, 0).frequency() * 2.0
+            );
+        }
+        let report = verify_tuning(&guitar, OctaveNotation::Scientific, Note::C);
+        assert_eq!(report.lines().count(), 6);
+        assert_eq!(
+            report.lines().last(),
+            Some("1\t0: E2 82.41 Hz\t5: A2 110.00 Hz\t12: E3 164.81 Hz")
+        );
+    }
+
+    #[test]
+    fn test_scale_centered_for_scale() {
+        use crate::scale::ScaleMode;
+        use Note::*;
+        let c_major = Scale {
+            start_note: C,
+            mode: ScaleMode::Major,
+        };
+        let open = |scale| {
+            Guitar::from_tuning_for_scale(6, E, 24, Tuning::ScaleCentered, scale)
+                .strings
+                .iter()
+                .map(|s| s.start)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(open(Some(&c_major)), vec![A, D, G, C, F, B]);
+        assert_eq!(open(None), vec![E, Gs, C, E, Gs, C]);
+    }
+
+    #[test]
+    fn test_lowest_string_fret_of() {
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Standard);
+        assert_eq!(guitar.lowest_string_fret_of(Note::A), Some(5));
+        assert_eq!(guitar.lowest_string_fret_of(Note::E), Some(0));
+        assert_eq!(guitar.lowest_string_fret_of(Note::Ds), Some(11));
+        let guitar = Guitar::from_tuning(0, Note::E, 24, Tuning::Fourths);
+        assert_eq!(guitar.lowest_string_fret_of(Note::A), None);
+    }
+
+    #[test]
+    fn test_custom_tuning() {
+        use Note::*;
+        let dadgad = "D,A,D,G,A,D".parse::<Tuning>().unwrap();
+        assert_eq!(dadgad, Tuning::Custom(vec![D, A, D, G, A, D]));
+        assert_eq!(dadgad.to_string(), "Custom D A D G A D");
+        let guitar = Guitar::from_tuning(6, E, 24, dadgad.clone());
+        let open = guitar.strings.iter().map(|s| s.start).collect::<Vec<_>>();
+        assert_eq!(Tuning::Custom(open), dadgad);
+        assert_eq!(guitar.open_pitches()[5], Pitch::new(D, 4));
+        assert_eq!("Standard".parse(), Ok(Tuning::Standard));
+        assert!("D,H".parse::<Tuning>().is_err());
+        assert_eq!(
+            Tuning::parse_with("D,A,D,G,H,E", NoteInput::German),
+            "D,A,D,G,B,E".parse()
+        );
+        assert_eq!(
+            "drop-d".parse::<Tuning>().unwrap_err().to_string(),
+            "invalid tuning `drop-d`, expected standard, fourths, fifths, scale-centered, \
+             dulcimer or comma separated notes"
+        );
+    }
+
+    #[test]
+    fn test_diatonic_fretting() {
+        use Note::*;
+        let string = GuitarString::diatonic(D, DULCIMER_STEPS.to_vec());
+        let notes = (0..9).map(|fret| string.note_at(fret)).collect::<Vec<_>>();
+        assert_eq!(notes, vec![D, E, Fs, G, A, B, C, D, E]);
+        assert_eq!(string.semitones_at(7), 12);
+        assert_eq!(string.frets_of(Fs, 0..15), vec![2, 9]);
+        let dulcimer = Guitar::from_tuning(6, E, 15, Tuning::Dulcimer);
+        assert_eq!(dulcimer.strings.len(), 3);
+        assert_eq!(
+            dulcimer.pitch_at(0, 7),
+            dulcimer.pitch_at(0, 0).offset_by(12)
+        );
+        assert_eq!(dulcimer.open_pitches()[1], dulcimer.open_pitches()[2]);
+    }
+
+    #[test]
+    fn test_caged_shapes() {
+        use crate::scale::ScaleMode;
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Standard);
+        let scale = |start_note| Scale {
+            start_note,
+            mode: ScaleMode::Major,
+        };
+        let shapes = guitar.shape_windows(&scale(Note::C), 4);
+        assert_eq!(
+            shapes,
+            vec![vec![0], vec![2, 3], vec![5], vec![7, 8], vec![10]]
+        );
+        for start_note in [Note::G, Note::E, Note::As] {
+            assert_eq!(guitar.shape_windows(&scale(start_note), 4).len(), 5);
+        }
+    }
+
+    #[test]
+    fn test_pentatonic_box() {
+        let guitar = Guitar::from_tuning(6, Note::E, 24, Tuning::Standard);
+        let first = guitar.pentatonic_box(Note::A, 1);
+        assert_eq!(first.len(), 12);
+        assert!(first.iter().all(|(_, fret)| (5..=8).contains(fret)));
+        assert_eq!(&first[..2], &[(0, 5), (0, 8)]);
+        let second = guitar.pentatonic_box(Note::A, 2);
+        assert!(second.iter().all(|(_, fret)| (7..=10).contains(fret)));
+        let notes = second
+            .iter()
+            .map(|(string, fret)| guitar.strings[*string].note_at(*fret))
+            .collect::<HashSet<_>>();
+        assert_eq!(notes, [Note::A, Note::C, Note::D, Note::E, Note::G].into());
+        let fifth = guitar.pentatonic_box(Note::A, 5);
+        assert!(fifth.iter().all(|(_, fret)| (14..=17).contains(fret)));
+    }
+
+    #[test]
+    fn test_find_voicing() {
+        let guitar = Guitar::from_tuning(6, Note::E, 15, Tuning::Standard);
+        let c_major = [Note::C, Note::E, Note::G];
+        let voicing = guitar.find_voicing(&c_major, 4).unwrap();
+        assert_eq!(voicing_name(&voicing), "x32010");
+        let fretted = voicing.iter().flatten().filter(|fret| **fret > 0);
+        let (low, high) = fretted.fold((usize::MAX, 0), |(low, high), fret| {
+            (low.min(*fret), high.max(*fret))
+        });
+        assert!(high - low < 4);
+        let voicing = guitar
+            .find_voicing(&[Note::E, Note::Gs, Note::B], 4)
+            .unwrap();
+        assert_eq!(voicing_name(&voicing), "022100");
+        assert_eq!(guitar.find_voicing(&[], 4), None);
+        assert_eq!(voicing_name(&[None, Some(12), Some(10)]), "x-12-10");
+    }
+
+    #[test]
+    fn test_transcribe() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let melody = [Note::C, Note::D, Note::E, Note::G, Note::C];
+        let positions = guitar.transcribe(&melody, 12).unwrap();
+        assert_eq!(positions.len(), melody.len());
+        let pitches = positions
+            .iter()
+            .map(|(string, fret)| guitar.pitch_at(*string, *fret))
+            .collect::<Vec<_>>();
+        assert!(pitches.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(pitches[0], Pitch::new(Note::C, 3));
+        let fretted = positions
+            .iter()
+            .map(|(_, fret)| *fret)
+            .filter(|fret| *fret > 0);
+        assert!(fretted.clone().max().unwrap() - fretted.min().unwrap() < 4);
+        assert_eq!(positions, [(1, 3), (2, 0), (2, 2), (3, 0), (3, 5)]);
+        let error = Guitar::from_tuning(1, Note::E, 3, Tuning::Standard)
+            .transcribe(&[Note::E, Note::Fs, Note::A, Note::E], 2)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            UnreachableNoteError {
+                index: 2,
+                note: Note::A,
+                max_fret: 2
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "note 3 of the melody, A, is on no string up to fret 2"
+        );
+    }
+
+    #[test]
+    fn test_voicing_score() {
+        let weights = VoicingWeights::default();
+        let open_c = [None, Some(3), Some(2), Some(0), Some(1), Some(0)];
+        let barre_c = [Some(8), Some(10), Some(10), Some(9), Some(8), Some(8)];
+        assert!(voicing_score(&open_c, weights) < voicing_score(&barre_c, weights));
+        let guitar = Guitar::from_tuning(6, Note::E, 15, Tuning::Standard);
+        let best = guitar
+            .best_voicing(&[Note::C, Note::E, Note::G], 4, weights)
+            .unwrap();
+        assert_eq!(voicing_name(&best), "x32010");
+        let only_stretch = VoicingWeights {
+            muted: 0,
+            barre: 0,
+            position: 0,
+            ..weights
+        };
+        assert_eq!(voicing_score(&barre_c, only_stretch), 4);
+    }
+
+    #[test]
+    fn test_find_voicing_on_strings() {
+        let guitar = Guitar::from_tuning(6, Note::E, 15, Tuning::Standard);
+        let c_major = [Note::C, Note::E, Note::G];
+        let voicing = guitar
+            .find_voicing_on(&c_major, 4, &[2, 3, 4])
+            .unwrap()
+            .unwrap();
+        assert_eq!(voicing_name(&voicing), "x320xx");
+        assert!(voicing
+            .iter()
+            .enumerate()
+            .all(|(index, fret)| fret.is_some() == (1..4).contains(&index)));
+        assert_eq!(guitar.find_voicing_on(&c_major, 4, &[1, 2]), Ok(None));
+    }
+
+    #[test]
+    fn test_find_voicing_on_invalid_strings() {
+        let guitar = Guitar::from_tuning(6, Note::E, 15, Tuning::Standard);
+        let c_major = [Note::C, Note::E, Note::G];
+        assert_eq!(
+            guitar.find_voicing_on(&c_major, 4, &[2, 7]),
+            Err(StringSetError::OutOfRange {
+                string: 7,
+                count: 6
+            })
+        );
+        assert_eq!(
+            guitar.find_voicing_on(&c_major, 4, &[0, 2]),
+            Err(StringSetError::OutOfRange {
+                string: 0,
+                count: 6
+            })
+        );
+        assert_eq!(
+            guitar.find_voicing_on(&c_major, 4, &[2, 3, 2]),
+            Err(StringSetError::Duplicate { string: 2 })
+        );
+        assert_eq!(
+            StringSetError::Duplicate { string: 2 }.to_string(),
+            "string 2 is listed more than once"
+        );
+    }
+
+    #[test]
+    fn test_verify_tuning_octave_boundary() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let report = verify_tuning(&guitar, OctaveNotation::Scientific, Note::A);
+        assert_eq!(
+            report.lines().last(),
+            Some("1\t0: E1 82.41 Hz\t5: A2 110.00 Hz\t12: E2 164.81 Hz")
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::pitch::Pitch;
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
+
+#[derive(Debug, EnumIter, EnumCount, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Interval {
+    Unison,
+    MinorSecond,
+    MajorSecond,
+    MinorThird,
+    MajorThird,
+    PerfectFourth,
+    Tritone,
+    PerfectFifth,
+    MinorSixth,
+    MajorSixth,
+    MinorSeventh,
+    MajorSeventh,
+    Octave,
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            Interval::Unison => "perfect unison",
+            Interval::MinorSecond => "minor second",
+            Interval::MajorSecond => "major second",
+            Interval::MinorThird => "minor third",
+            Interval::MajorThird => "major third",
+            Interval::PerfectFourth => "perfect fourth",
+            Interval::Tritone => "tritone",
+            Interval::PerfectFifth => "perfect fifth",
+            Interval::MinorSixth => "minor sixth",
+            Interval::MajorSixth => "major sixth",
+            Interval::MinorSeventh => "minor seventh",
+            Interval::MajorSeventh => "major seventh",
+            Interval::Octave => "perfect octave",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+impl Interval {
+    pub fn semitones(self) -> usize {
+        self as usize
+    }
+
+    /// the usual abbreviation, such as `m3` or `P5`
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Interval::Unison => "P1",
+            Interval::MinorSecond => "m2",
+            Interval::MajorSecond => "M2",
+            Interval::MinorThird => "m3",
+            Interval::MajorThird => "M3",
+            Interval::PerfectFourth => "P4",
+            Interval::Tritone => "TT",
+            Interval::PerfectFifth => "P5",
+            Interval::MinorSixth => "m6",
+            Interval::MajorSixth => "M6",
+            Interval::MinorSeventh => "m7",
+            Interval::MajorSeventh => "M7",
+            Interval::Octave => "P8",
+        }
+    }
+
+    /// `semitones` up to an octave, anything wider is reduced to a simple interval
+    pub fn from_semitones(semitones: usize) -> Self {
+        match semitones {
+            0 => Interval::Unison,
+            _ => Self::iter()
+                .nth((semitones - 1) % (Self::COUNT - 1) + 1)
+                .expect("reduced below the interval count"),
+        }
+    }
+
+    /// the interval between two pitches regardless of their order,
+    /// along with the number of whole octaves it spans beyond a simple interval
+    pub fn between(a: Pitch, b: Pitch) -> (Self, usize) {
+        let distance = (b.semitones() - a.semitones()).unsigned_abs() as usize;
+        let octaves = distance.saturating_sub(1) / 12;
+        (Self::from_semitones(distance), octaves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::Note;
+
+    #[test]
+    fn test_interval_between_pitches() {
+        let e2 = Pitch::new(Note::E, 2);
+        let a2 = Pitch::new(Note::A, 2);
+        assert_eq!(Interval::between(e2, a2), (Interval::PerfectFourth, 0));
+        assert_eq!(Interval::between(a2, e2), (Interval::PerfectFourth, 0));
+        assert_eq!(Interval::between(e2, e2), (Interval::Unison, 0));
+        assert_eq!(
+            Interval::between(e2, Pitch::new(Note::E, 3)),
+            (Interval::Octave, 0)
+        );
+        assert_eq!(
+            Interval::between(e2, Pitch::new(Note::B, 3)),
+            (Interval::PerfectFifth, 1)
+        );
+        assert_eq!(Interval::PerfectFourth.to_string(), "perfect fourth");
+    }
+}
@@ -0,0 +1,257 @@
+use crate::note::Note;
+use crate::scale::{Scale, ScaleMode};
+
+/// number of sharps (positive) or flats (negative), as on the circle of fifths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeySignature {
+    pub fifths: i32,
+}
+
+impl std::fmt::Display for KeySignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.fifths {
+            0 => write!(f, "0"),
+            sharps if sharps > 0 => write!(f, "{sharps}#"),
+            flats => write!(f, "{}b", -flats),
+        }
+    }
+}
+
+impl KeySignature {
+    /// signature of the major key on `root`, preferring F# over Gb and Db over C#
+    pub fn of_major(root: Note) -> Self {
+        let fifths = (root.index() as i32 * 7).rem_euclid(12);
+        Self {
+            fifths: match fifths > 6 {
+                true => fifths - 12,
+                false => fifths,
+            },
+        }
+    }
+}
+
+/// the major key whose signature is used to notate the scale: the major scale sharing
+/// its notes, or for scales outside the major modes the (relative) major of its third
+pub fn key_major(scale: &Scale) -> Scale {
+    let major = |start_note| Scale {
+        start_note,
+        mode: ScaleMode::Major,
+    };
+    (0..12)
+        .map(|offset| major(scale.start_note.offset_by(offset)))
+        .find(|candidate| candidate.bitmask() == scale.bitmask())
+        .unwrap_or_else(|| match scale.has_minor_third() {
+            true => major(scale.start_note.offset_by(3)),
+            false => major(scale.start_note),
+        })
+}
+
+pub fn key_signature(scale: &Scale) -> KeySignature {
+    KeySignature::of_major(key_major(scale).start_note)
+}
+
+/// relative minor for major scales, the signature's major key for everything else
+pub fn relative_key(scale: &Scale) -> Scale {
+    match scale.mode {
+        ScaleMode::Major => Scale {
+            start_note: scale.start_note.offset_by(9),
+            mode: ScaleMode::Minor,
+        },
+        _ => key_major(scale),
+    }
+}
+
+const LETTERS: [(char, usize); 7] = [
+    ('C', 0),
+    ('D', 2),
+    ('E', 4),
+    ('F', 5),
+    ('G', 7),
+    ('A', 9),
+    ('B', 11),
+];
+
+/// semitones each degree sits above (positive) or below the natural of its letter, the
+/// letters walking up from `root_letter`; a bebop passing note repeats the letter below it
+fn letter_offsets(scale: &Scale, root_letter: usize) -> Vec<(char, i32)> {
+    let passing = scale.mode.passing_degree();
+    scale
+        .degrees()
+        .into_iter()
+        .enumerate()
+        .map(|(degree, note)| {
+            let step = degree - passing.is_some_and(|passing| degree >= passing) as usize;
+            let (letter, natural) = LETTERS[(root_letter + step) % LETTERS.len()];
+            let offset = (note.index() as i32 - natural as i32 + 6).rem_euclid(12) - 6;
+            (letter, offset)
+        })
+        .collect()
+}
+
+/// the degrees with one letter each, starting from whichever spelling of the root needs
+/// fewer accidentals (sharps on a tie), e.g. `Bb` in F major
+pub fn spelled_degrees(scale: &Scale) -> Vec<String> {
+    let accidentals = |offsets: &[(char, i32)]| offsets.iter().map(|(_, o)| o.abs()).sum::<i32>();
+    let offsets = scale
+        .start_note
+        .enharmonic_names()
+        .into_iter()
+        .filter_map(|name| {
+            LETTERS
+                .iter()
+                .position(|(letter, _)| name.starts_with(*letter))
+        })
+        .map(|root_letter| letter_offsets(scale, root_letter))
+        .min_by_key(|offsets| accidentals(offsets))
+        .unwrap_or_default();
+    offsets
+        .into_iter()
+        .map(|(letter, offset)| {
+            let accidental = match offset > 0 {
+                true => "#".repeat(offset as usize),
+                false => "b".repeat(-offset as usize),
+            };
+            format!("{letter}{accidental}")
+        })
+        .collect()
+}
+
+/// `note` as the scale spells it, or with the scale's kind of accidental (sharps on a tie)
+/// when it is not one of its degrees
+pub fn spell_note(scale: &Scale, note: Note) -> String {
+    match scale.degrees().iter().position(|degree| *degree == note) {
+        Some(index) => spelled_degrees(scale)[index].clone(),
+        None => {
+            let (sharps, flats) = count_accidentals(scale);
+            match flats > sharps {
+                true => note.flat_name().to_string(),
+                false => note.to_string(),
+            }
+        }
+    }
+}
+
+/// sharps and flats in the spelled degrees, a double sharp counting twice
+pub fn count_accidentals(scale: &Scale) -> (usize, usize) {
+    spelled_degrees(scale)
+        .iter()
+        .fold((0, 0), |(sharps, flats), name| {
+            (
+                sharps + name.matches('#').count(),
+                flats + name[1..].matches('b').count(),
+            )
+        })
+}
+
+/// `no notes`, `1 note`, `2 notes`
+pub fn plural(count: usize, noun: &str) -> String {
+    match count {
+        0 => format!("no {noun}s"),
+        1 => format!("1 {noun}"),
+        count => format!("{count} {noun}s"),
+    }
+}
+
+/// `D Major is spelled D E F# G A B C#, with 2 sharps and no flats`
+pub fn accidentals_line(scale: &Scale) -> String {
+    let (sharps, flats) = count_accidentals(scale);
+    format!(
+        "{scale} is spelled {}, with {} and {}",
+        spelled_degrees(scale).join(" "),
+        plural(sharps, "sharp"),
+        plural(flats, "flat")
+    )
+}
+
+/// root, mode, notes, key signature and relative key separated by tabs
+pub fn summary_line(scale: &Scale) -> String {
+    let notes = scale
+        .degrees()
+        .iter()
+        .map(|note| note.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "{}\t{:?}\t{}\t{}\t{}",
+        scale.start_note,
+        scale.mode,
+        notes,
+        key_signature(scale),
+        relative_key(scale)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scale(start_note: Note, mode: ScaleMode) -> Scale {
+        Scale { start_note, mode }
+    }
+
+    #[test]
+    fn test_key_signatures() {
+        use Note::*;
+        assert_eq!(key_signature(&scale(C, ScaleMode::Major)).to_string(), "0");
+        assert_eq!(key_signature(&scale(D, ScaleMode::Major)).to_string(), "2#");
+        assert_eq!(key_signature(&scale(F, ScaleMode::Major)).to_string(), "1b");
+        assert_eq!(key_signature(&scale(E, ScaleMode::Minor)).fifths, 1);
+        assert_eq!(key_signature(&scale(D, ScaleMode::Dorian)).fifths, 0);
+        assert_eq!(key_signature(&scale(A, ScaleMode::MelodicMinor)).fifths, 0);
+        assert_eq!(
+            relative_key(&scale(A, ScaleMode::Minor)).to_string(),
+            "C Major"
+        );
+    }
+
+    #[test]
+    fn test_accidentals() {
+        use Note::*;
+        assert_eq!(count_accidentals(&scale(D, ScaleMode::Major)), (2, 0));
+        assert_eq!(count_accidentals(&scale(F, ScaleMode::Major)), (0, 1));
+        assert_eq!(
+            spelled_degrees(&scale(F, ScaleMode::Major)),
+            ["F", "G", "A", "Bb", "C", "D", "E"]
+        );
+        assert_eq!(count_accidentals(&scale(Cs, ScaleMode::Major)), (0, 5));
+        assert_eq!(
+            count_accidentals(&scale(A, ScaleMode::HungarianMinor)),
+            (2, 0)
+        );
+        assert_eq!(
+            spelled_degrees(&scale(G, ScaleMode::BebopDominant)),
+            ["G", "A", "B", "C", "D", "E", "F", "F#"]
+        );
+        assert_eq!(
+            spelled_degrees(&scale(Ds, ScaleMode::BebopMajor)),
+            ["Eb", "F", "G", "Ab", "Bb", "B", "C", "D"]
+        );
+        assert_eq!(
+            accidentals_line(&scale(D, ScaleMode::Major)),
+            "D Major is spelled D E F# G A B C#, with 2 sharps and no flats"
+        );
+    }
+
+    #[test]
+    fn test_summary_line() {
+        assert_eq!(
+            summary_line(&scale(Note::C, ScaleMode::Major)),
+            "C\tMajor\tC D E F G A B\t0\tA Minor"
+        );
+    }
+
+    #[test]
+    fn test_summary_line_in_scale_order() {
+        for scale in Scale::all() {
+            let line = summary_line(&scale);
+            let notes = line.split('\t').nth(2).unwrap();
+            let expected = scale
+                .degrees()
+                .iter()
+                .map(|note| note.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(notes, expected.join(" "));
+            assert_eq!(summary_line(&scale), line);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::note::Note;
+use crate::render::{colored, ROOT_COLOR};
+use crate::scale::Scale;
+use strum::IntoEnumIterator;
+
+/// character width of one white key, its left border included
+const WHITE_KEY_WIDTH: usize = 4;
+/// marks a black key outside the scale
+const UNUSED_BLACK_KEY: &str = "##";
+
+fn is_black(note: Note) -> bool {
+    note.to_string().ends_with('#')
+}
+
+fn label(note: Note, scale: &Scale, no_color: bool) -> String {
+    let name = note.to_string();
+    match note == scale.start_note && !no_color {
+        true => colored(&name, ROOT_COLOR),
+        false => name,
+    }
+}
+
+/// `octaves` piano octaves from C: black keys on the first line, sitting between the white
+/// keys of the second; scale notes are named (the root colored unless `no_color`), other white
+/// keys left blank
+pub fn render_keyboard(scale: &Scale, octaves: usize, no_color: bool) -> String {
+    let notes = scale.notes();
+    let keys = Note::iter()
+        .cycle()
+        .take(Note::iter().len() * octaves.max(1))
+        .collect::<Vec<_>>();
+    let mut black_row = String::new();
+    let mut black_width = 0;
+    let mut white_row = String::new();
+    let mut white_keys = 0;
+    for note in keys {
+        match is_black(note) {
+            true => {
+                let column = white_keys * WHITE_KEY_WIDTH - 1;
+                black_row.push_str(&" ".repeat(column - black_width));
+                match notes.contains(&note) {
+                    true => black_row.push_str(&label(note, scale, no_color)),
+                    false => black_row.push_str(UNUSED_BLACK_KEY),
+                }
+                black_width = column + UNUSED_BLACK_KEY.len();
+            }
+            false => {
+                let name = match notes.contains(&note) {
+                    true => label(note, scale, no_color),
+                    false => " ".to_string(),
+                };
+                white_row.push_str(&format!("| {name} "));
+                white_keys += 1;
+            }
+        }
+    }
+    format!("{black_row}\n{white_row}|\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scale::ScaleMode;
+
+    #[test]
+    fn test_c_major_keyboard() {
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let keyboard = render_keyboard(&scale, 1, false);
+        let (black, white) = keyboard.trim_end().split_once('\n').unwrap();
+        assert_eq!(
+            white,
+            format!("| {} | D | E | F | G | A | B |", colored("C", ROOT_COLOR))
+        );
+        assert_eq!(black, "   ##  ##      ##  ##  ##");
+    }
+
+    #[test]
+    fn test_keyboard_without_color() {
+        let scale = Scale {
+            start_note: Note::Fs,
+            mode: ScaleMode::Major,
+        };
+        let keyboard = render_keyboard(&scale, 1, true);
+        assert!(!keyboard.contains('\x1b'));
+        assert!(keyboard.contains("F#"));
+    }
+
+    #[test]
+    fn test_two_octave_keyboard() {
+        let scale = Scale {
+            start_note: Note::E,
+            mode: ScaleMode::Major,
+        };
+        let keyboard = render_keyboard(&scale, 2, false);
+        let (black, white) = keyboard.trim_end().split_once('\n').unwrap();
+        assert_eq!(white.matches('|').count(), 15);
+        assert_eq!(white.matches(" C ").count(), 0);
+        assert_eq!(black.matches("C#").count(), 2);
+        assert_eq!(black.matches(UNUSED_BLACK_KEY).count(), 2);
+        assert_eq!(black.len(), 4 * 13 + 1);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::chord::{Chord, ChordSize, TriadOverlay};
+use crate::guitar::{Guitar, GuitarString};
+use crate::note::Note;
+use crate::pitch::Pitch;
+use crate::render::{
+    fret_range, Labels, RenderOptions, BASS_COLOR, CHORD_COLOR, DEGREE_COLORS, HIGHLIGHT_COLOR,
+    PASSING_COLOR, ROOT_COLOR, TENSION_COLOR,
+};
+use crate::scale::Scale;
+use std::collections::HashSet;
+use std::ops::Range;
+use strum::EnumCount;
+
+/// one fret of one string, everything a renderer needs to draw it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub fret: usize,
+    pub pitch: Pitch,
+    pub in_scale: bool,
+    pub is_root: bool,
+    /// `None` when the cell is left empty
+    pub label: Option<String>,
+    /// terminal color code of the label
+    pub color: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutString {
+    /// zero-based index into the guitar strings, lowest first
+    pub index: usize,
+    /// `1(E)`, the string number followed by the open note
+    pub label: String,
+    pub cells: Vec<Cell>,
+}
+
+/// backend agnostic neck: strings lowest first, each with a cell per shown fret
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub frets: Range<usize>,
+    pub strings: Vec<LayoutString>,
+}
+
+/// the color of the first marked triad holding `note`; with every triad marked, a bebop passing
+/// note is in none of them and gets a color of its own
+fn triad_overlay_color(note: Note, scale: &Scale, overlay: &TriadOverlay) -> Option<&'static str> {
+    let passing = scale.notes().contains(&note) && !scale.diatonic_degrees().contains(&note);
+    match (overlay, passing) {
+        (TriadOverlay::All, true) => Some(PASSING_COLOR),
+        _ => overlay
+            .degrees(scale)
+            .into_iter()
+            .find(|degree| scale.chord(*degree, ChordSize::Triad).contains(&note))
+            .map(|degree| DEGREE_COLORS[(degree - 1) % DEGREE_COLORS.len()]),
+    }
+}
+
+/// precedence: scale root, chord bass, chord tones, chord tensions, triad overlay, highlighted
+/// notes, plain scale notes
+fn cell_style(
+    note: Note,
+    scale: &Scale,
+    scale_notes: &HashSet<Note>,
+    options: &RenderOptions,
+) -> Option<(String, Option<&'static str>)> {
+    let chord = options.chord.as_ref();
+    let name = match (options.labels, scale.solfege(note)) {
+        (Labels::Solfege, Some(syllable)) => syllable.to_string(),
+        (Labels::SolfegeFixed, _) => note.fixed_do().to_string(),
+        _ => note.to_string(),
+    };
+    let triad_color = options
+        .triad_overlay
+        .as_ref()
+        .and_then(|overlay| triad_overlay_color(note, scale, overlay));
+    match triad_color {
+        _ if note == scale.start_note => Some((name, Some(ROOT_COLOR))),
+        _ if chord.map(Chord::bass) == Some(note) => Some((name, Some(BASS_COLOR))),
+        _ if chord.is_some_and(|chord| chord.notes().contains(&note)) => {
+            Some((name, Some(CHORD_COLOR)))
+        }
+        _ if options.tensions.contains(&note) => Some((name, Some(TENSION_COLOR))),
+        Some(color) => Some((name, Some(color))),
+        _ if options.highlight.contains(&note) => Some((name, Some(HIGHLIGHT_COLOR))),
+        _ if scale_notes.contains(&note) => match (options.labels, options.all_note_names) {
+            (Labels::Names, false) => Some(("O".to_string(), None)),
+            _ => Some((name, None)),
+        },
+        _ => None,
+    }
+}
+
+/// `R` for the lowest root pitch shown, `R+1`, `R+2`... for the octaves above it
+fn root_octave_style(
+    pitch: Pitch,
+    lowest_root: Option<Pitch>,
+) -> Option<(String, Option<&'static str>)> {
+    let lowest = lowest_root.filter(|lowest| lowest.note == pitch.note)?;
+    match (pitch.semitones() - lowest.semitones()) / Note::COUNT as i32 {
+        0 => Some(("R".to_string(), Some(ROOT_COLOR))),
+        octaves => Some((format!("R+{octaves}"), Some(HIGHLIGHT_COLOR))),
+    }
+}
+
+impl Layout {
+    /// each shown string's label with the number of cells that get one, highest string first
+    pub fn labeled_per_string(&self) -> Vec<(&str, usize)> {
+        self.strings
+            .iter()
+            .rev()
+            .map(|string| {
+                let count = string
+                    .cells
+                    .iter()
+                    .filter(|cell| cell.label.is_some())
+                    .count();
+                (string.label.as_str(), count)
+            })
+            .collect()
+    }
+
+    pub fn new(guitar: &Guitar, scale: &Scale, options: &RenderOptions) -> Self {
+        let notes = scale.notes();
+        let frets = fret_range(guitar, options);
+        let open_pitches = guitar.open_pitches();
+        let pitch_at = |string: &GuitarString, open: &Pitch, fret| {
+            open.offset_by(string.semitones_at(fret) as _)
+        };
+        let shown = guitar
+            .strings
+            .iter()
+            .zip(&open_pitches)
+            .enumerate()
+            .filter(|(index, _)| !options.skip_strings.contains(&(index + 1)))
+            .collect::<Vec<_>>();
+        let lowest_root = shown
+            .iter()
+            .flat_map(|(_, (string, open))| frets.clone().map(|fret| pitch_at(string, open, fret)))
+            .filter(|pitch| pitch.note == scale.start_note)
+            .min();
+        let strings = shown
+            .into_iter()
+            .map(|(index, (string, open))| {
+                let span = options.between.map(|(low, high)| {
+                    let low = open.offset_by(open.note.ascending_distance(low) as _);
+                    (low, low.offset_by(low.note.ascending_distance(high) as _))
+                });
+                let cells = frets
+                    .clone()
+                    .map(|fret| {
+                        let pitch = pitch_at(string, open, fret);
+                        let hidden = options
+                            .only_positions
+                            .as_ref()
+                            .is_some_and(|shown| !shown.contains(&(index, fret)))
+                            || options
+                                .range
+                                .as_ref()
+                                .is_some_and(|range| !range.contains(&pitch))
+                            || span.is_some_and(|(low, high)| pitch < low || pitch > high);
+                        let style = match (hidden, options.only_root_octaves) {
+                            (true, _) => None,
+                            (false, true) => root_octave_style(pitch, lowest_root),
+                            (false, false) => cell_style(pitch.note, scale, &notes, options),
+                        };
+                        let (label, color) = style.unzip();
+                        let color = match label {
+                            Some(_) => options
+                                .note_colors
+                                .get(&pitch.note)
+                                .map(|color| Some(color.code()))
+                                .unwrap_or(color.flatten()),
+                            None => None,
+                        };
+                        Cell {
+                            fret,
+                            pitch,
+                            in_scale: notes.contains(&pitch.note),
+                            is_root: pitch.note == scale.start_note,
+                            label,
+                            color,
+                        }
+                    })
+                    .collect();
+                LayoutString {
+                    index,
+                    label: format!("{}({})", index + 1, string.start),
+                    cells,
+                }
+            })
+            .collect();
+        Self { frets, strings }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guitar::Tuning;
+    use crate::scale::ScaleMode;
+
+    #[test]
+    fn test_layout_cells() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let layout = Layout::new(&guitar, &scale, &RenderOptions::default());
+        assert_eq!(layout.strings.len(), 6);
+        assert!(layout.strings.iter().all(|string| string.cells.len() == 13));
+        let roots = layout
+            .strings
+            .iter()
+            .enumerate()
+            .flat_map(|(index, string)| {
+                string
+                    .cells
+                    .iter()
+                    .filter(|cell| cell.is_root)
+                    .map(move |cell| (index, cell.fret))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(roots, guitar.positions_of(Note::C, 12));
+        let low_c = &layout.strings[1].cells[3];
+        assert_eq!(low_c.pitch, Pitch::new(Note::C, 3));
+        assert_eq!(low_c.label.as_deref(), Some("C"));
+        assert_eq!(low_c.color, Some(ROOT_COLOR));
+        let open_e = &layout.strings[0].cells[0];
+        assert!(open_e.in_scale && !open_e.is_root);
+        assert_eq!(open_e.label.as_deref(), Some("O"));
+        assert_eq!(layout.strings[0].cells[2].label, None);
+        assert_eq!(layout.strings[0].label, "1(E)");
+    }
+
+    #[test]
+    fn test_pitch_range() {
+        let guitar = Guitar::from_tuning(6, Note::C, 25, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let range = Pitch::new(Note::E, 2)..=Pitch::new(Note::A, 4);
+        let options = RenderOptions {
+            range: Some(range.clone()),
+            ..RenderOptions::default()
+        };
+        let layout = Layout::new(&guitar, &scale, &options);
+        let cells = layout.strings.iter().flat_map(|string| &string.cells);
+        let (inside, outside): (Vec<_>, Vec<_>) = cells
+            .filter(|cell| cell.in_scale)
+            .partition(|cell| range.contains(&cell.pitch));
+        assert!(outside.iter().any(|cell| cell.pitch < *range.start()));
+        assert!(outside.iter().any(|cell| cell.pitch > *range.end()));
+        assert!(outside.iter().all(|cell| cell.label.is_none()));
+        assert!(inside.iter().all(|cell| cell.label.is_some()));
+    }
+
+    #[test]
+    fn test_tension_colors() {
+        let guitar = Guitar::from_tuning(1, Note::C, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let chord: Chord = "Cmaj7".parse().unwrap();
+        let options = RenderOptions {
+            chord: Some(chord),
+            tensions: chord
+                .available_tensions(&scale)
+                .into_iter()
+                .map(|(_, note)| note)
+                .collect(),
+            ..RenderOptions::default()
+        };
+        let cells = &Layout::new(&guitar, &scale, &options).strings[0].cells;
+        assert_eq!(cells[2].color, Some(TENSION_COLOR));
+        assert_eq!(cells[9].color, Some(TENSION_COLOR));
+        assert_eq!(cells[4].color, Some(CHORD_COLOR));
+        assert_eq!(cells[5].color, None);
+    }
+
+    #[test]
+    fn test_notes_between() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = RenderOptions {
+            between: Some((Note::G, Note::C)),
+            ..RenderOptions::default()
+        };
+        let layout = Layout::new(&guitar, &scale, &options);
+        let marked = layout.strings[0]
+            .cells
+            .iter()
+            .filter(|cell| cell.label.is_some())
+            .map(|cell| cell.fret)
+            .collect::<Vec<_>>();
+        assert_eq!(marked, [3, 5, 7, 8]);
+        let guitar = Guitar::from_tuning(6, Note::E, 25, Tuning::Standard);
+        let layout = Layout::new(&guitar, &scale, &options);
+        let marked = |string: usize| {
+            layout.strings[string]
+                .cells
+                .iter()
+                .filter(|cell| cell.label.is_some())
+                .map(|cell| cell.fret)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(marked(0), [3, 5, 7, 8]);
+        assert_eq!(marked(1), [10, 12, 14, 15]);
+    }
+
+    #[test]
+    fn test_labeled_per_string() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let layout = Layout::new(&guitar, &scale, &RenderOptions::default());
+        assert_eq!(
+            layout.labeled_per_string(),
+            [
+                ("6(E)", 8),
+                ("5(B)", 8),
+                ("4(G)", 8),
+                ("3(D)", 8),
+                ("2(A)", 8),
+                ("1(E)", 8)
+            ]
+        );
+        let options = RenderOptions {
+            frets_start: 1,
+            skip_strings: [2].into(),
+            ..RenderOptions::default()
+        };
+        let layout = Layout::new(&guitar, &scale, &options);
+        let counts = layout.labeled_per_string();
+        assert_eq!(counts.len(), 5);
+        assert_eq!(counts[0], ("6(E)", 7));
+    }
+}
@@ -0,0 +1,23 @@
+pub mod audio;
+pub mod chord;
+pub mod drill;
+pub mod guitar;
+pub mod interval;
+pub mod key;
+pub mod keyboard;
+pub mod layout;
+#[cfg(feature = "midi-input")]
+pub mod listen;
+pub mod midi;
+pub mod musicxml;
+pub mod note;
+pub mod pitch;
+#[cfg(feature = "serde")]
+pub mod practice_log;
+pub mod random;
+#[cfg(feature = "png")]
+pub mod raster;
+pub mod render;
+pub mod repl;
+pub mod scale;
+pub mod set_theory;
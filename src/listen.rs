@@ -0,0 +1,31 @@
+use crate::midi::{collect_pitch_classes, listen_report, note_on_number};
+use midir::MidiInput;
+use std::error::Error;
+
+/// reports the played notes and the scales containing them after every note-on from the
+/// first midi input port, until enter is pressed
+pub fn listen() -> Result<(), Box<dyn Error>> {
+    let input = MidiInput::new("infinity-board")?;
+    let ports = input.ports();
+    let port = ports.first().ok_or("no midi input port found")?;
+    println!(
+        "LISTENING: {} (press enter to stop)",
+        input.port_name(port)?
+    );
+    let _connection = input
+        .connect(
+            port,
+            "infinity-board-listen",
+            |_, message, played: &mut Vec<u8>| {
+                if let Some(number) = note_on_number(message) {
+                    played.push(number);
+                    let notes = collect_pitch_classes(played.iter().copied());
+                    println!("{}\n", listen_report(&notes));
+                }
+            },
+            Vec::new(),
+        )
+        .map_err(|error| error.to_string())?;
+    std::io::stdin().read_line(&mut String::new())?;
+    Ok(())
+}
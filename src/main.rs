@@ -1,248 +1,974 @@
-use clap::Parser;
-use clap::ValueEnum;
-use derive_more::Constructor;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
+use infinity_board::audio;
+use infinity_board::chord::{
+    self, fit_scales, tritone_sub_line, Chord, ChordInput, ChordSize, TriadOverlay,
+};
+use infinity_board::drill::{
+    interval_prompts, note_finder_prompts, render_interval_drill, render_note_finder, Drill,
+};
+use infinity_board::guitar::{self, Guitar, Tuning, VoicingWeights};
+use infinity_board::key::{accidentals_line, summary_line};
+use infinity_board::keyboard::render_keyboard;
+use infinity_board::midi::{midi_file, scale_sequence, MidiOptions, NoteDuration};
+use infinity_board::musicxml;
+use infinity_board::note::{note_bitmask, parse_note_span, Note, NoteInput};
+use infinity_board::pitch::{parse_pitch_range, OctaveNotation, Pitch};
+#[cfg(feature = "serde")]
+use infinity_board::practice_log::{self, PracticeLogEntry};
+use infinity_board::random::rng_from_seed;
+use infinity_board::render::{
+    debug_report, parse_note_colors, per_string_line, render_batch, render_fretboard,
+    render_fretless, render_heatmap, render_mode_grid, render_named_tab, render_progression,
+    render_tab, Labels, Orientation, RenderOptions,
+};
+use infinity_board::repl::{ReplState, REPL_HELP};
+use infinity_board::scale::{self, Scale, ScaleMode};
+use infinity_board::set_theory;
 use itertools::Itertools;
-use std::collections::HashSet;
 use std::fmt::Display;
-use strum::{EnumCount, EnumIter, IntoEnumIterator};
+use std::io::{BufRead, Read, Write};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::str::FromStr;
 
-#[derive(
-    Debug, EnumIter, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumCount, Hash, ValueEnum,
-)]
-pub enum Note {
-    C,
-    Cs,
-    D,
-    Ds,
-    E,
-    F,
-    Fs,
-    G,
-    Gs,
-    A,
-    As,
-    B,
-}
-
-impl std::fmt::Display for Note {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let repr = match self {
-            Note::C => "C",
-            Note::Cs => "C#",
-            Note::D => "D",
-            Note::Ds => "D#",
-            Note::E => "E",
-            Note::F => "F",
-            Note::Fs => "F#",
-            Note::G => "G",
-            Note::Gs => "G#",
-            Note::A => "A",
-            Note::As => "A#",
-            Note::B => "B",
-        };
-        write!(f, "{}", repr)
-    }
-}
-
-impl Note {
-    fn cycle() -> impl Iterator<Item = Self> {
-        Self::iter().cycle()
-    }
-
-    fn cycle_from(self) -> impl Iterator<Item = Self> {
-        Self::cycle().skip_while(move |i| i != &self)
-    }
-
-    fn offset_by(self, offset: i32) -> Self {
-        let offset = offset.wrapping_rem_euclid(Self::COUNT as i32);
-        self.cycle_from()
-            .skip(offset as _)
-            .next()
-            .expect("this is an infinite stream, come on")
-    }
-}
-
-#[derive(Debug, Constructor)]
-struct GuitarString {
-    start: Note,
-}
-
-#[derive(Debug)]
-struct Guitar {
-    strings: Vec<GuitarString>,
-    notes_per_string: usize,
-}
-
-#[derive(
-    Debug, EnumIter, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumCount, Hash, ValueEnum,
-)]
-pub enum Tuning {
-    Fourths,
-    ScaleCentered,
-}
-impl Guitar {
-    pub fn from_tuning(
-        string_count: usize,
-        start: Note,
-        notes_per_string: usize,
-        tuning: Tuning,
-    ) -> Self {
-        let strings = match tuning {
-            Tuning::Fourths => start
-                .cycle_from()
-                .step_by(5)
-                .take(string_count)
-                .map(GuitarString::new)
-                .collect(),
-            Tuning::ScaleCentered => {
-                let intervals: Vec<usize> = vec![4, 4, 4, 4];
-                let mut output = vec![start];
-                intervals
-                    .iter()
-                    .cycle()
-                    .take(string_count)
-                    .for_each(|interval| {
-                        let last = output.last().expect("it is not empty").clone();
-                        output.push(last.offset_by(*interval as _));
-                    });
-                output.into_iter().map(GuitarString::new).collect()
-            }
-        };
-        Self {
-            strings,
-            notes_per_string,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, ValueEnum)]
-pub enum ScaleMode {
-    Major,
-}
-
-impl ScaleMode {
-    pub fn intervals_raw(self) -> Vec<usize> {
-        match self {
-            ScaleMode::Major => {
-                vec![2, 2, 1, 2, 2, 2, 1]
-            }
-        }
-    }
-    pub fn intervals(self) -> impl Iterator<Item = usize> {
-        self.intervals_raw().into_iter().cycle()
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Scale {
-    start_note: Note,
-    mode: ScaleMode,
-}
-
-impl Display for Scale {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let Self { start_note, mode } = self;
-        write!(f, "{start_note} {mode:?}")
-    }
-}
-
-impl Scale {
-    pub fn notes_list(&self) -> Vec<Note> {
-        let mut notes = vec![self.start_note];
-        let intervals = self.mode.intervals_raw();
-        intervals.iter().for_each(|interval| {
-            let latest = notes
-                .last()
-                .cloned()
-                .unwrap_or(self.start_note)
-                .offset_by(*interval as _);
-            notes.push(latest)
-        });
-        notes
-    }
-
-    pub fn notes(&self) -> HashSet<Note> {
-        self.notes_list().into_iter().collect()
-    }
-}
-
-#[derive(Parser)]
+#[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     #[arg(long)]
-    start_note: Note,
+    start_note: Option<String>,
+    /// how every note argument is written, chord symbols such as Bb7 excepted
+    #[arg(long, default_value = "english")]
+    note_input: NoteInput,
     #[arg(long)]
-    mode: ScaleMode,
+    mode: Option<ScaleMode>,
+    /// required unless --tuning lists the open strings, which must then number the same
     #[arg(long)]
-    string_count: usize,
+    string_count: Option<usize>,
     #[arg(long)]
     all_note_names: bool,
+    /// what the scale notes are labeled with
+    #[arg(long, default_value = "names")]
+    labels: Labels,
     #[arg(long, default_value = "0")]
     frets_start: usize,
+    /// start the window at the first fret playing this note on the lowest string
+    #[arg(long, conflicts_with = "frets_start")]
+    frets_start_note: Option<String>,
     #[arg(long, default_value = "24")]
     frets_end: usize,
+    /// standard, fourths, fifths, scale-centered, dulcimer or the open strings such as
+    /// D,A,D,G,A,D
     #[arg(long, default_value = "fourths")]
-    tuning: Tuning,
+    tuning: String,
+    /// lowest open string of the standard, fourths and fifths tunings; dulcimer is always D,A,A
+    /// and scale-centered is laid out around the scale root
+    #[arg(long, default_value = "E")]
+    tuning_root: String,
+    /// shift every open string by this many semitones, negative values tune down
+    #[arg(long, default_value = "0", allow_negative_numbers = true)]
+    capo: i32,
+    #[arg(long, default_value = "landscape")]
+    orientation: Orientation,
+    #[arg(long, default_value = "\t")]
+    separator: String,
+    /// pad every cell to its column width instead of relying on tab stops
+    #[arg(long)]
+    fixed_width: bool,
+    /// name the major scale the mode is a rotation of
+    #[arg(long)]
+    show_parent: bool,
+    /// count the closed (CAGED) shapes of the scale across the neck
+    #[arg(long)]
+    count_shapes: bool,
+    /// print how many notes are marked on each string of the shown frets
+    #[arg(long)]
+    count_positions_per_string: bool,
+    /// print how many note pairs of the scale, and of the chord if any, lie each interval class
+    /// apart
+    #[arg(long)]
+    interval_vector: bool,
+    /// print the scale mirrored around this note, naming it when it is a known mode
+    #[arg(long)]
+    invert_around: Option<String>,
+    /// print the chord built on every degree of the scale
+    #[arg(long)]
+    diatonic_chords: bool,
+    #[arg(long, default_value = "triad")]
+    chord_size: ChordSize,
+    /// start at the 1st fret even when --frets-start is 0
+    #[arg(long)]
+    no_open: bool,
+    /// print the fret numbers above the neck
+    #[arg(long)]
+    fret_numbers: bool,
+    /// shift the fret numbers printed in the header and the tab, e.g. 1 to count the open
+    /// string as fret 1
+    #[arg(long, default_value = "0", allow_negative_numbers = true)]
+    fret_number_offset: i32,
+    /// split the neck into blocks of this many frets
+    #[arg(long)]
+    wrap: Option<usize>,
+    /// draw the neck with box-drawing borders and a title bar
+    #[arg(long)]
+    fancy: bool,
+    /// draw each string as a continuous ruler marking the notes at their true positions
+    #[arg(long, conflicts_with_all = ["fancy", "wrap"])]
+    fretless: bool,
+    /// print how many scale notes each stretch of four frets holds instead of the notes
+    #[arg(long)]
+    heatmap: bool,
+    /// mark only the root, the lowest one as R and the octaves above as R+1, R+2...
+    #[arg(long)]
+    only_root_octaves: bool,
+    /// mark only the Nth (1-5) minor pentatonic box of the root
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=5))]
+    pentatonic_box: Option<u8>,
+    /// comma separated string numbers, 1 being the lowest, left out of the neck
+    #[arg(long, value_delimiter = ',')]
+    skip_strings: Vec<usize>,
+    /// comma separated notes colored wherever they appear on the neck
+    #[arg(long, value_delimiter = ',')]
+    highlight_notes: Vec<String>,
+    /// colors for specific notes such as `C=red,G=green`, winning over every other color
+    #[arg(long)]
+    note_colors: Option<String>,
+    /// print plain text without colors
+    #[arg(long)]
+    no_color: bool,
+    /// wraps the root without colors: two characters like `()` open and close, anything
+    /// else goes on both sides
+    #[arg(long, default_value = "()")]
+    root_marker: String,
+    /// mark only the pitches within a range such as `E2:A4`, middle C being C4
+    #[arg(long, value_parser = parse_pitch_range)]
+    range: Option<RangeInclusive<Pitch>>,
+    /// mark only the notes from the first up to the next second on each string, such as `G:C`;
+    /// the span starts at the lowest first note of the string and is not repeated an octave up
+    #[arg(long)]
+    between: Option<String>,
+    /// chord whose tones (and slash bass) are colored on top of the scale, as a symbol such as
+    /// Am7 or as its notes, root first, such as A,C,E,G
+    #[arg(long)]
+    chord: Option<String>,
+    /// drop notes repeated in a --chord note list, such as Fb after E, instead of refusing it
+    #[arg(long)]
+    simplify: bool,
+    /// put this chord tone (1 for the third, 2 for the fifth...) of --chord and --voice in the bass
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=3))]
+    inversion: Option<u8>,
+    /// color a chord's tones and, apart from them, its tensions available in the scale
+    #[arg(long, conflicts_with = "chord")]
+    chord_extensions: Option<Chord>,
+    /// mark diatonic triads (roman numerals such as IV or I,IV,V) or `all` of them, each triad in
+    /// the color of its degree and a shared note in that of the first one listed
+    #[arg(long)]
+    triad_overlay: Option<TriadOverlay>,
+    /// report whether a chord belongs to the scale, or where it is borrowed from
+    #[arg(long)]
+    analyze_chord: Option<Chord>,
+    /// print the frequency of a tuning reference such as `A4` and write it as a held tone to
+    /// --export-wav
+    #[arg(long, value_parser = Pitch::from_str)]
+    reference: Option<Pitch>,
+    /// the frequency of A4 in Hz that --reference is calibrated to
+    #[arg(long, default_value = "440", value_parser = audio::parse_frequency)]
+    a4: f64,
+    /// write the --reference tone to this WAV file; it is only written, not played back
+    #[arg(long, requires = "reference")]
+    export_wav: Option<PathBuf>,
+    /// write the scale, up and back down, as a MIDI file
+    #[arg(long)]
+    export_midi: Option<PathBuf>,
+    /// write the scale ascending, with its key signature and spelling, as a MusicXML score
+    #[arg(long)]
+    export_musicxml: Option<PathBuf>,
+    /// append the rendered scale, tuning and chord with a timestamp as a JSON line to this file
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    log: Option<PathBuf>,
+    /// write the neck as a PNG image
+    #[cfg(feature = "png")]
+    #[arg(long)]
+    export_png: Option<PathBuf>,
+    /// fill every whole step of the exported MIDI sequence with a softer chromatic passing tone
+    #[arg(long, requires = "export_midi")]
+    chromatic_approaches: bool,
+    #[arg(long, default_value = "120")]
+    tempo: u32,
+    #[arg(long, default_value = "quarter")]
+    note_duration: NoteDuration,
+    #[arg(long)]
+    drill: Option<Drill>,
+    /// number of drill prompts
+    #[arg(long, default_value = "20")]
+    count: usize,
+    #[arg(long)]
+    seed: Option<u64>,
+    /// pick the root and mode at random, reproducible with --seed
+    #[arg(long, conflicts_with_all = ["start_note", "mode"])]
+    random_scale: bool,
+    #[arg(long)]
+    show_answers: bool,
+    /// the scale as comma separated pitch classes, 0 being C, rooted on the lowest one unless
+    /// --start-note names another
+    #[arg(long, value_delimiter = ',', conflicts_with_all = ["mode", "random_scale"])]
+    pitch_classes: Vec<usize>,
+    /// print a single tab separated line: root, mode, notes, key signature, relative key
+    #[arg(long)]
+    summary: bool,
+    /// print only the comma separated scale notes
+    #[arg(long)]
+    dump_notes: bool,
+    /// describe in a sentence how the scale is built from its intervals
+    #[arg(long)]
+    explain: bool,
+    /// print every mode of the scale with its notes, the ones setting it apart highlighted
+    #[arg(long)]
+    compare_modes: bool,
+    /// print the scale spelled with one letter per degree and its sharps and flats
+    #[arg(long)]
+    count_accidentals: bool,
+    /// print the scale on this many piano octaves (1 by default) instead of the neck
+    #[arg(long, num_args = 0..=1, default_missing_value = "1")]
+    keyboard: Option<usize>,
+    /// render every scale read from stdin, one per line such as `A dorian`
+    #[arg(long, conflicts_with_all = ["start_note", "mode", "random_scale"])]
+    batch: bool,
+    /// read commands such as `scale C major` or `transpose +2` from stdin, re-rendering after each
+    #[arg(long, conflicts_with = "batch")]
+    repl: bool,
+    /// print the open, 5th and 12th fret frequencies of every string to check intonation
+    #[arg(long)]
+    verify: bool,
+    /// how --verify writes octaves: scientific (C4 is middle C) or helmholtz (c′)
+    #[arg(long, value_enum, default_value_t = OctaveNotation::Scientific)]
+    octave_notation: OctaveNotation,
+    /// the note --verify starts each octave number on, C as in scientific pitch notation
+    #[arg(long, default_value = "C")]
+    octave_boundary: String,
+    /// print a playable fingering of this chord, lowest string first
+    #[arg(long)]
+    voice: Option<Chord>,
+    /// the most frets a --voice or --best-voicing fingering may span
+    #[arg(long, default_value_t = 4)]
+    max_stretch: usize,
+    /// comma separated string numbers, 1 being the lowest, the --voice fingering is played on
+    #[arg(long, value_delimiter = ',')]
+    voicing_strings: Vec<usize>,
+    /// print the easiest fingering of this chord anywhere on the neck
+    #[arg(long)]
+    best_voicing: Option<Chord>,
+    /// draw a chord diagram for each chord, such as `C,Am,F,G`, side by side
+    #[arg(long, value_delimiter = ',')]
+    progression: Vec<Chord>,
+    /// print single note tab for a melody such as `C,E,G,C`
+    #[arg(long, value_delimiter = ',')]
+    transcribe: Vec<String>,
+    /// name the --transcribe notes under the tab, spelled as in the scale
+    #[arg(long, requires = "transcribe")]
+    tab_names: bool,
+    /// print every mode with its interval pattern
+    #[arg(long, exclusive = true)]
+    list_modes: bool,
+    /// print every tuning with its open strings
+    #[arg(long, exclusive = true)]
+    list_tunings: bool,
+    /// report the scales matching the notes played on the first midi input, live
+    #[cfg(feature = "midi-input")]
+    #[arg(long)]
+    listen: bool,
+    /// two comma separated scales such as C:major,A:minor to compare note by note
+    #[arg(long, value_delimiter = ',', num_args = 1, exclusive = true)]
+    compare_scales: Vec<Scale>,
+    /// comma separated chords to find a common scale for
+    #[arg(long, value_delimiter = ',')]
+    fit_scale: Vec<Chord>,
+    /// print the scales holding every tone of this chord, the most usual choices first
+    #[arg(long, exclusive = true)]
+    scales_over: Option<Chord>,
+    /// print the parsed arguments, the guitar, the scale and the render options first
+    #[arg(long)]
+    verbose: bool,
+    /// print the modes this chord is the tonic chord of, such as dorian and minor for Dm7
+    #[arg(long, exclusive = true)]
+    modes_for: Option<Chord>,
+    /// print the normal and prime forms of a pitch class set such as `C,E,G`, taking no other
+    /// argument but --note-input
+    #[arg(long, value_delimiter = ',')]
+    pc_set_analysis: Vec<String>,
+    /// print the dominant a tritone away from this one, such as Db7 for G7
+    #[arg(long, exclusive = true)]
+    tritone_sub: Option<Chord>,
+}
+
+/// arguments only some of the modes need, reported the way clap reports missing arguments
+fn required<T>(value: Option<T>, name: &str) -> T {
+    value.unwrap_or_else(|| {
+        Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                format!("the following required argument was not provided: {name}"),
+            )
+            .exit()
+    })
+}
+
+/// an argument holding notes in the `--note-input` convention, reported the way clap reports
+/// invalid values
+fn parse_arg<T, E: Display>(
+    input: &str,
+    flag: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> T {
+    parse(input).unwrap_or_else(|error| {
+        Cli::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("invalid value for {flag}: {error}"),
+            )
+            .exit()
+    })
+}
+
+/// the scale of `--pitch-classes`, rerooted on `--start-note` when given
+fn pitch_class_scale(pitch_classes: &[usize], root: Option<Note>) -> Scale {
+    let fail = |message: String| {
+        Cli::command()
+            .error(ErrorKind::InvalidValue, message)
+            .exit()
+    };
+    let scale = Scale::from_pitch_classes(pitch_classes)
+        .unwrap_or_else(|error| fail(format!("invalid --pitch-classes: {error}")));
+    match root {
+        Some(root) => Scale::from_bitmask(root, scale.bitmask())
+            .unwrap_or_else(|| fail(format!("{root} is not one of the --pitch-classes"))),
+        None => scale,
+    }
+}
+
+/// fails like `exclusive = true` when --pc-set-analysis comes with any argument besides the
+/// --note-input its notes are read with
+fn check_pc_set_analysis_alone(matches: &ArgMatches) {
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    if !given("pc_set_analysis") {
+        return;
+    }
+    let command = Cli::command();
+    let other = command
+        .get_arguments()
+        .filter(|arg| !["pc_set_analysis", "note_input"].contains(&arg.get_id().as_str()))
+        .find(|arg| given(arg.get_id().as_str()));
+    if let Some(long) = other.and_then(|arg| arg.get_long()) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!("the argument '--pc-set-analysis' cannot be used with '--{long}'"),
+            )
+            .exit()
+    }
 }
 
 fn main() {
+    let matches = Cli::command().get_matches();
+    check_pc_set_analysis_alone(&matches);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+    if cli.verbose {
+        println!("CLI: {cli:#?}");
+    }
     let Cli {
         start_note,
+        note_input,
         mode,
         string_count,
         all_note_names,
+        labels,
         frets_start,
+        frets_start_note,
         frets_end,
         tuning,
-    } = Cli::parse();
-    let my_tuning = Guitar::from_tuning(string_count, Note::E, frets_end, tuning);
-    let scale = Scale { start_note, mode };
-    let notes = scale.notes();
-    println!("SCALE: {scale}");
+        tuning_root,
+        capo,
+        orientation,
+        separator,
+        fixed_width,
+        show_parent,
+        count_shapes,
+        count_positions_per_string,
+        interval_vector,
+        invert_around,
+        diatonic_chords,
+        chord_size,
+        no_open,
+        fret_numbers,
+        fret_number_offset,
+        wrap,
+        fancy,
+        fretless,
+        heatmap,
+        only_root_octaves,
+        pentatonic_box,
+        skip_strings,
+        highlight_notes,
+        note_colors,
+        no_color,
+        root_marker,
+        range,
+        between,
+        chord,
+        simplify,
+        inversion,
+        chord_extensions,
+        triad_overlay,
+        analyze_chord,
+        reference,
+        a4,
+        export_wav,
+        export_midi,
+        chromatic_approaches,
+        export_musicxml,
+        #[cfg(feature = "serde")]
+        log,
+        #[cfg(feature = "png")]
+        export_png,
+        tempo,
+        note_duration,
+        drill,
+        count,
+        seed,
+        random_scale,
+        pitch_classes,
+        show_answers,
+        summary,
+        dump_notes,
+        explain,
+        compare_modes,
+        count_accidentals,
+        keyboard,
+        batch,
+        repl,
+        verify,
+        octave_notation,
+        octave_boundary,
+        voice,
+        best_voicing,
+        voicing_strings,
+        max_stretch,
+        progression,
+        transcribe,
+        tab_names,
+        list_modes,
+        list_tunings,
+        #[cfg(feature = "midi-input")]
+        listen,
+        compare_scales,
+        fit_scale,
+        tritone_sub,
+        pc_set_analysis,
+        scales_over,
+        modes_for,
+        verbose,
+    } = cli;
+    let parse_note = |name: &str, flag| parse_arg(name, flag, |name| note_input.parse(name));
+    let start_note = start_note.map(|name| parse_note(&name, "--start-note"));
+    let frets_start_note = frets_start_note.map(|name| parse_note(&name, "--frets-start-note"));
+    let tuning_root = parse_note(&tuning_root, "--tuning-root");
+    let invert_around = invert_around.map(|name| parse_note(&name, "--invert-around"));
+    let octave_boundary = parse_note(&octave_boundary, "--octave-boundary");
+    let parse_notes = |names: Vec<String>, flag| {
+        names
+            .iter()
+            .map(|name| parse_note(name, flag))
+            .collect::<Vec<_>>()
+    };
+    let highlight_notes = parse_notes(highlight_notes, "--highlight-notes");
+    let transcribe = parse_notes(transcribe, "--transcribe");
+    let pc_set_analysis = parse_notes(pc_set_analysis, "--pc-set-analysis");
+    let tuning = parse_arg(&tuning, "--tuning", |input| {
+        Tuning::parse_with(input, note_input)
+    });
+    let note_colors = note_colors.map(|input| {
+        parse_arg(&input, "--note-colors", |input| {
+            parse_note_colors(input, note_input)
+        })
+    });
+    let between = between.map(|input| {
+        parse_arg(&input, "--between", |input| {
+            parse_note_span(input, note_input)
+        })
+    });
+    let chord = chord.map(|input| {
+        parse_arg(&input, "--chord", |input| {
+            ChordInput::parse_with(input, note_input)
+        })
+    });
+    let invert = |chord: Option<Chord>| match (chord, inversion) {
+        (Some(chord), Some(inversion)) => {
+            Some(chord.inverted(inversion as usize).unwrap_or_else(|| {
+                Cli::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        format!("{chord} has no inversion {inversion}"),
+                    )
+                    .exit()
+            }))
+        }
+        (chord, _) => chord,
+    };
+    let chord = chord.map(|input| match input {
+        ChordInput::Symbol(chord) => chord,
+        ChordInput::Notes(set) => {
+            let duplicates = set.duplicates();
+            if !duplicates.is_empty() && !simplify {
+                let repeats = duplicates
+                    .iter()
+                    .map(|(name, first)| format!("{name} is the same note as {first}"))
+                    .join(", ");
+                Cli::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        format!("invalid --chord: {repeats}, pass --simplify to drop repeats"),
+                    )
+                    .exit()
+            }
+            let notes = set.simplified();
+            Chord::from_notes(&notes).unwrap_or_else(|| {
+                Cli::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        format!("{} is not a known chord", notes.iter().join(",")),
+                    )
+                    .exit()
+            })
+        }
+    });
+    let (chord, voice) = (invert(chord.or(chord_extensions)), invert(voice));
+    if list_modes {
+        print!("{}", scale::list_modes());
+        return;
+    }
+    if list_tunings {
+        print!("{}", guitar::list_tunings());
+        return;
+    }
+    #[cfg(feature = "midi-input")]
+    if listen {
+        if let Err(error) = infinity_board::listen::listen() {
+            eprintln!("error: {error}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    if !compare_scales.is_empty() {
+        match compare_scales.as_slice() {
+            [a, b] => print!("{}", scale::compare_scales(a, b)),
+            _ => Cli::command()
+                .error(
+                    ErrorKind::WrongNumberOfValues,
+                    "--compare-scales takes exactly two scales",
+                )
+                .exit(),
+        }
+        return;
+    }
+    if let Some(chord) = scales_over {
+        println!(
+            "SCALES OVER {chord}: {}",
+            chord::scales_over(&chord).iter().join(", ")
+        );
+        return;
+    }
+    if let Some(chord) = modes_for {
+        println!(
+            "MODES FOR {chord}: {}",
+            chord::modes_for(&chord).iter().join(", ")
+        );
+        return;
+    }
+    if let Some(pitch) = reference {
+        println!(
+            "REFERENCE: {pitch} at {:.2} Hz (A4 = {a4} Hz)",
+            pitch.frequency_at(a4)
+        );
+        if let Some(path) = export_wav {
+            std::fs::write(&path, audio::reference_tone(pitch, a4)).expect("writing the wav file");
+            println!("WAV: {}", path.display());
+        }
+        return;
+    }
+    if !pc_set_analysis.is_empty() {
+        let notes = pc_set_analysis.iter().copied().collect();
+        println!("NORMAL FORM: {:?}", set_theory::normal_form(&notes));
+        println!("PRIME FORM: {:?}", set_theory::prime_form(&notes));
+        return;
+    }
+    if let Some(chord) = tritone_sub {
+        let line = tritone_sub_line(&chord).unwrap_or_else(|| {
+            Cli::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!("{chord} is not a dominant seventh chord, such as G7"),
+                )
+                .exit()
+        });
+        println!("TRITONE SUB: {line}");
+        return;
+    }
+    if !fit_scale.is_empty() {
+        println!("CHORDS: {}", fit_scale.iter().join(", "));
+        println!(
+            "FITTING SCALES: {}",
+            fit_scales(&fit_scale).iter().join(", ")
+        );
+        return;
+    }
+    let scale = || match (random_scale, pitch_classes.is_empty()) {
+        (true, _) => Scale::random(&mut rng_from_seed(seed)),
+        (false, false) => pitch_class_scale(&pitch_classes, start_note),
+        (false, true) => Scale {
+            start_note: required(start_note, "--start-note"),
+            mode: required(mode, "--mode"),
+        },
+    };
+    if summary {
+        let scale = scale();
+        println!("{}", summary_line(&scale));
+        return;
+    }
+    if dump_notes {
+        println!("{}", scale::dump_notes(&scale()));
+        return;
+    }
+    if compare_modes {
+        print!("{}", render_mode_grid(&scale(), no_color));
+        return;
+    }
+    if explain {
+        let scale = scale();
+        println!("{}", scale::explain(&scale));
+        println!("{}", accidentals_line(&scale));
+        return;
+    }
+    if count_accidentals {
+        println!("{}", accidentals_line(&scale()));
+        return;
+    }
+    if let Some(octaves) = keyboard {
+        print!("{}", render_keyboard(&scale(), octaves, no_color));
+        return;
+    }
+    let string_count = match (&tuning, string_count) {
+        (Tuning::Custom(notes), None) => notes.len(),
+        (Tuning::Custom(notes), Some(count)) if count != notes.len() => Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "--string-count {count} does not match the {} strings of --tuning",
+                    notes.len()
+                ),
+            )
+            .exit(),
+        (_, count) => required(count, "--string-count"),
+    };
+    let guitar_for = |scale: Option<&Scale>| {
+        Guitar::from_tuning_for_scale(string_count, tuning_root, frets_end, tuning.clone(), scale)
+            .with_capo(capo)
+    };
+    let my_tuning = guitar_for(None);
+    let frets_start = frets_start_note
+        .and_then(|note| my_tuning.lowest_string_fret_of(note))
+        .unwrap_or(frets_start);
+    if frets_start >= frets_end {
+        eprintln!("warning: the fret window {frets_start}..{frets_end} is empty");
+    }
+    if verify {
+        print!(
+            "{}",
+            guitar::verify_tuning(&my_tuning, octave_notation, octave_boundary)
+        );
+        return;
+    }
+    if !transcribe.is_empty() {
+        let positions = my_tuning
+            .transcribe(&transcribe, frets_end.saturating_sub(1))
+            .unwrap_or_else(|error| {
+                Cli::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        format!("invalid --transcribe: {error}"),
+                    )
+                    .exit()
+            });
+        match tab_names {
+            true => print!(
+                "{}",
+                render_named_tab(&my_tuning, &positions, fret_number_offset, &scale())
+            ),
+            false => print!("{}", render_tab(&my_tuning, &positions, fret_number_offset)),
+        }
+        return;
+    }
+    if !progression.is_empty() {
+        let diagrams =
+            render_progression(&my_tuning, &progression, max_stretch).unwrap_or_else(|error| {
+                Cli::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        format!("invalid --progression: {error}"),
+                    )
+                    .exit()
+            });
+        print!("{diagrams}");
+        return;
+    }
+    if let Some(chord) = best_voicing {
+        let weights = VoicingWeights::default();
+        match my_tuning.best_voicing(&chord.voiced_notes(), max_stretch, weights) {
+            Some(voicing) => println!(
+                "VOICING: {chord} {} (difficulty {})",
+                guitar::voicing_name(&voicing),
+                guitar::voicing_score(&voicing, weights)
+            ),
+            None => println!("VOICING: {chord} has no fingering within {max_stretch} frets"),
+        }
+        return;
+    }
+    if let Some(chord) = voice {
+        let voicing = match voicing_strings.is_empty() {
+            true => my_tuning.find_voicing(&chord.voiced_notes(), max_stretch),
+            false => my_tuning
+                .find_voicing_on(&chord.voiced_notes(), max_stretch, &voicing_strings)
+                .unwrap_or_else(|error| {
+                    Cli::command()
+                        .error(
+                            ErrorKind::InvalidValue,
+                            format!("invalid --voicing-strings: {error}"),
+                        )
+                        .exit()
+                }),
+        };
+        match voicing {
+            Some(voicing) => println!("VOICING: {chord} {}", guitar::voicing_name(&voicing)),
+            None => println!("VOICING: {chord} has no fingering within {max_stretch} frets"),
+        }
+        return;
+    }
+    let mut options = RenderOptions {
+        all_note_names,
+        labels,
+        frets_start,
+        orientation,
+        separator,
+        fixed_width,
+        highlight: highlight_notes.into_iter().collect(),
+        skip_strings: skip_strings.into_iter().collect(),
+        only_positions: None,
+        chord,
+        tensions: Vec::new(),
+        triad_overlay,
+        no_open,
+        fret_numbers,
+        fret_number_offset,
+        fancy,
+        only_root_octaves,
+        wrap,
+        range,
+        between,
+        no_color,
+        root_marker,
+        note_colors: note_colors.unwrap_or_default(),
+    };
+    if batch {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .expect("reading the scales from stdin");
+        print!("{}", render_batch(&input, &my_tuning, &options));
+        return;
+    }
+    if repl {
+        let mut state = ReplState {
+            scale: match (start_note, mode) {
+                (Some(start_note), Some(mode)) => Scale { start_note, mode },
+                _ => Scale {
+                    start_note: Note::C,
+                    mode: ScaleMode::Major,
+                },
+            },
+            tuning,
+            string_count,
+            frets_end,
+            capo,
+            options,
+        };
+        println!("{REPL_HELP}");
+        print!("{}", state.render());
+        for line in std::io::stdin().lock().lines() {
+            let line = line.expect("reading a command from stdin");
+            match state.execute(&line) {
+                Some(output) => print!("{output}"),
+                None => break,
+            }
+            std::io::stdout().flush().expect("flushing stdout");
+        }
+        return;
+    }
+    if let Some(drill) = drill {
+        let mut rng = rng_from_seed(seed);
+        let drill = match drill {
+            Drill::NoteFinder => {
+                let prompts = note_finder_prompts(&my_tuning, count, &mut rng);
+                render_note_finder(&my_tuning, &prompts, frets_start..frets_end, show_answers)
+            }
+            Drill::Interval => {
+                let prompts = interval_prompts(&my_tuning, count, frets_start..frets_end, &mut rng);
+                render_interval_drill(&my_tuning, &prompts, show_answers)
+            }
+        };
+        print!("{drill}");
+        return;
+    }
+    let scale = scale();
+    let my_tuning = guitar_for(Some(&scale));
+    if let Some(number) = pentatonic_box {
+        let positions = my_tuning.pentatonic_box(scale.start_note, number as usize);
+        options.only_positions = Some(positions.into_iter().collect());
+    }
+    let tensions = chord_extensions
+        .and(chord)
+        .map(|chord| chord.available_tensions(&scale))
+        .unwrap_or_default();
+    options.tensions = tensions.iter().map(|(_, note)| *note).collect();
+    let mode = scale.mode;
+    match (show_parent, scale.parent_major()) {
+        (true, Some(parent)) => println!("SCALE: {scale} (parent: {parent})"),
+        (true, None) => println!("SCALE: {scale} (no parent major)"),
+        (false, _) => println!("SCALE: {scale}"),
+    }
     println!(
         "NOTES: {}",
         scale.notes_list().iter().map(|n| n.to_string()).join(", ")
     );
-    println!();
-    for (num, string) in my_tuning
-        .strings
-        .iter()
-        .enumerate()
-        .map(|(i, val)| (i + 1, val))
-        .rev()
-    {
-        print!("{num}({})\t", string.start);
-        for note in string
-            .start
-            .cycle_from()
-            .skip(frets_start)
-            .take(my_tuning.notes_per_string - frets_start)
-        {
-            let print_note = |note: &Note| match scale.start_note.eq(note) {
-                true => print!("\t\x1b[93m{note}\x1b[0m"),
-                false => print!("\t{note}"),
-            };
-            match notes.contains(&note) {
-                true => match all_note_names {
-                    true => print_note(&note),
-                    false => match note.eq(&scale.start_note) {
-                        true => print_note(&note),
-                        false => print!("\tO"),
-                    },
-                },
-                false => print!("\t|"),
+    if mode.descending_intervals_raw() != mode.intervals_raw() {
+        println!(
+            "DESCENDING: {}",
+            scale
+                .descending_notes_list()
+                .iter()
+                .map(|n| n.to_string())
+                .join(", ")
+        );
+    }
+    if count_shapes {
+        let shapes = my_tuning.shape_windows(&scale, 4);
+        let starts = shapes.iter().map(|shape| match shape.as_slice() {
+            [only] => only.to_string(),
+            [first, .., last] => format!("{first}-{last}"),
+            [] => String::new(),
+        });
+        println!(
+            "SHAPES: {} (starting at frets {}), {} three notes per string patterns",
+            shapes.len(),
+            starts.format(", "),
+            scale.degrees().len()
+        );
+    }
+    if count_positions_per_string {
+        println!(
+            "PER STRING: {}",
+            per_string_line(&my_tuning, &scale, &options)
+        );
+    }
+    if interval_vector {
+        println!(
+            "INTERVAL VECTOR: {:?}",
+            set_theory::interval_vector(&scale.notes())
+        );
+        if let Some(chord) = &chord {
+            let notes = chord.notes().into_iter().collect();
+            println!(
+                "CHORD INTERVAL VECTOR: {:?}",
+                set_theory::interval_vector(&notes)
+            );
+        }
+    }
+    if let Some(axis) = invert_around {
+        let inverted = scale.invert(axis);
+        let root = scale.start_note.mirror(axis);
+        let notes = inverted
+            .iter()
+            .sorted_by_key(|note| root.ascending_distance(**note))
+            .join(", ");
+        match Scale::from_bitmask(root, note_bitmask(inverted)) {
+            Some(inversion) => println!("INVERSION AROUND {axis}: {inversion} ({notes})"),
+            None => println!("INVERSION AROUND {axis}: {notes}"),
+        }
+    }
+    if diatonic_chords {
+        println!("DIATONIC CHORDS:");
+        for (degree, chord) in scale.diatonic_chords(chord_size).iter().enumerate() {
+            println!("{}: {}", degree + 1, chord.iter().join(", "));
+        }
+    }
+    if let Some(path) = export_midi {
+        let options = MidiOptions {
+            tempo,
+            note_duration,
+            chromatic_approaches,
+        };
+        std::fs::write(&path, midi_file(&scale_sequence(&scale, 3), &options))
+            .expect("writing the midi file");
+        println!("MIDI: {}", path.display());
+    }
+    if let Some(path) = export_musicxml {
+        std::fs::write(&path, musicxml::scale_score(&scale)).expect("writing the musicxml file");
+        println!("MUSICXML: {}", path.display());
+    }
+    #[cfg(feature = "serde")]
+    if let Some(path) = log {
+        let entry = PracticeLogEntry::now(scale, tuning.clone(), chord.as_ref());
+        practice_log::append_entry(&path, &entry).expect("appending to the practice log");
+    }
+    if let Some(chord) = chord {
+        println!("CHORD: {chord} ({})", chord.notes().iter().join(", "));
+        match chord.inversion() {
+            Some(0) | None => {}
+            Some(inversion) => println!("INVERSION: {inversion}"),
+        }
+        if chord_extensions.is_some() {
+            let tensions = tensions
+                .iter()
+                .map(|(name, note)| format!("{name} ({note})"));
+            println!("TENSIONS: {}", tensions.format(", "));
+        }
+    }
+    if let Some(chord) = analyze_chord {
+        let notes = chord.notes();
+        match (scale.is_diatonic(&notes), scale.borrowed_from(&notes)) {
+            (true, _) => println!("ANALYSIS: {chord} is diatonic to {scale}"),
+            (false, Some(source)) => {
+                println!("ANALYSIS: {chord} is not diatonic to {scale}, borrowed from {source}")
             }
+            (false, None) => println!("ANALYSIS: {chord} is not diatonic to {scale}"),
         }
-        print!("\n");
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_offset() {
-        assert_eq!(Note::C.offset_by(1), Note::Cs);
-        assert_eq!(Note::C.offset_by(-1), Note::B);
+    #[cfg(feature = "png")]
+    if let Some(path) = export_png {
+        std::fs::write(
+            &path,
+            infinity_board::raster::render_png(&my_tuning, &scale, &options),
+        )
+        .expect("writing the png file");
+        println!("PNG: {}", path.display());
+    }
+    if verbose {
+        print!("{}", debug_report(&my_tuning, &scale, &options));
+    }
+    println!();
+    match (heatmap, fretless) {
+        (true, _) => print!("{}", render_heatmap(&my_tuning, &scale, &options)),
+        (false, true) => print!("{}", render_fretless(&my_tuning, &scale, &options)),
+        (false, false) => print!("{}", render_fretboard(&my_tuning, &scale, &options)),
     }
 }
@@ -0,0 +1,211 @@
+use crate::note::{note_bitmask, notes_of_bitmask, Note};
+use crate::pitch::Pitch;
+use crate::scale::{chromatic_approaches, scales_containing, Direction, Scale, PRACTICE_OCTAVE};
+use clap::ValueEnum;
+use itertools::Itertools;
+use strum::EnumCount;
+
+pub const TICKS_PER_QUARTER: u16 = 480;
+const VELOCITY: u8 = 100;
+/// chromatic passing tones are played softer than the scale notes around them
+const PASSING_VELOCITY: u8 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NoteDuration {
+    Half,
+    Quarter,
+    Eighth,
+}
+
+impl NoteDuration {
+    pub fn ticks(self) -> u32 {
+        let quarter = TICKS_PER_QUARTER as u32;
+        match self {
+            NoteDuration::Half => quarter * 2,
+            NoteDuration::Quarter => quarter,
+            NoteDuration::Eighth => quarter / 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MidiOptions {
+    pub tempo: u32,
+    pub note_duration: NoteDuration,
+    /// fill whole steps with [chromatic_approaches]
+    pub chromatic_approaches: bool,
+}
+
+impl Default for MidiOptions {
+    fn default() -> Self {
+        Self {
+            tempo: 120,
+            note_duration: NoteDuration::Quarter,
+            chromatic_approaches: false,
+        }
+    }
+}
+
+impl MidiOptions {
+    pub fn microseconds_per_quarter(&self) -> u32 {
+        60_000_000 / self.tempo.max(1)
+    }
+}
+
+/// one octave up from the root at `octave` and back down, see [Scale::practice_sequence]
+pub fn scale_sequence(scale: &Scale, octave: i32) -> Vec<Pitch> {
+    let shift = (octave - PRACTICE_OCTAVE) * Note::COUNT as i32;
+    scale
+        .practice_sequence(1, Direction::Both)
+        .into_iter()
+        .map(|pitch| pitch.offset_by(shift))
+        .collect()
+}
+
+fn variable_length(mut value: u32) -> Vec<u8> {
+    let mut bytes = vec![(value & 0x7f) as u8];
+    value >>= 7;
+    while value > 0 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.reverse();
+    bytes
+}
+
+fn track_events(pitches: &[Pitch], options: &MidiOptions) -> Vec<u8> {
+    let mut events = vec![0x00, 0xff, 0x51, 0x03];
+    events.extend_from_slice(&options.microseconds_per_quarter().to_be_bytes()[1..]);
+    let notes = match options.chromatic_approaches {
+        true => chromatic_approaches(pitches),
+        false => pitches.iter().map(|pitch| (*pitch, false)).collect(),
+    };
+    for (pitch, passing) in notes {
+        let key = pitch.midi_number().clamp(0, 127) as u8;
+        let velocity = match passing {
+            true => PASSING_VELOCITY,
+            false => VELOCITY,
+        };
+        events.extend([0x00, 0x90, key, velocity]);
+        events.extend(variable_length(options.note_duration.ticks()));
+        events.extend([0x80, key, 0]);
+    }
+    events.extend([0x00, 0xff, 0x2f, 0x00]);
+    events
+}
+
+/// a single-track standard MIDI file playing the pitches one after another
+pub fn midi_file(pitches: &[Pitch], options: &MidiOptions) -> Vec<u8> {
+    let events = track_events(pitches, options);
+    let mut bytes = b"MThd".to_vec();
+    bytes.extend(6u32.to_be_bytes());
+    bytes.extend(0u16.to_be_bytes());
+    bytes.extend(1u16.to_be_bytes());
+    bytes.extend(TICKS_PER_QUARTER.to_be_bytes());
+    bytes.extend(b"MTrk");
+    bytes.extend((events.len() as u32).to_be_bytes());
+    bytes.extend(events);
+    bytes
+}
+
+/// the note number of a note-on message, `None` for everything else including the
+/// zero velocity note-ons some keyboards send instead of note-offs
+pub fn note_on_number(message: &[u8]) -> Option<u8> {
+    match message {
+        [status, number, velocity, ..] if status & 0xf0 == 0x90 && *velocity > 0 => Some(*number),
+        _ => None,
+    }
+}
+
+/// the distinct pitch classes among played midi note numbers, from C upwards
+pub fn collect_pitch_classes(numbers: impl IntoIterator<Item = u8>) -> Vec<Note> {
+    let notes = numbers
+        .into_iter()
+        .map(|number| Pitch::from_midi_number(number as i32).note);
+    notes_of_bitmask(note_bitmask(notes))
+}
+
+/// `PLAYED: ...` and the scales containing every played note
+pub fn listen_report(played: &[Note]) -> String {
+    let scales = scales_containing(played.iter().copied());
+    format!(
+        "PLAYED: {}\nMATCHES: {}",
+        played.iter().join(", "),
+        match scales.is_empty() {
+            true => "none".to_string(),
+            false => scales.iter().join(", "),
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scale::ScaleMode;
+
+    #[test]
+    fn test_eighth_note_ticks_at_120_bpm() {
+        let options = MidiOptions {
+            tempo: 120,
+            note_duration: NoteDuration::Eighth,
+            chromatic_approaches: false,
+        };
+        assert_eq!(options.microseconds_per_quarter(), 500_000);
+        let bytes = midi_file(&[Pitch::new(Note::C, 4)], &options);
+        assert_eq!(&bytes[..4], b"MThd");
+        let track = &bytes[22..];
+        assert_eq!(&track[..7], &[0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20]);
+        // note on, then a 240 tick delta (0x81 0x70) before the note off
+        assert_eq!(&track[7..11], &[0x00, 0x90, 60, 100]);
+        assert_eq!(&track[11..13], &variable_length(240)[..]);
+        assert_eq!(&track[11..13], &[0x81, 0x70]);
+        assert_eq!(&track[13..16], &[0x80, 60, 0]);
+        let options = MidiOptions {
+            chromatic_approaches: true,
+            ..options
+        };
+        let bytes = midi_file(&[Pitch::new(Note::C, 4), Pitch::new(Note::D, 4)], &options);
+        let track = &bytes[22..];
+        assert_eq!(&track[16..20], &[0x00, 0x90, 61, PASSING_VELOCITY]);
+        assert_eq!(&track[25..29], &[0x00, 0x90, 62, VELOCITY]);
+    }
+
+    #[test]
+    fn test_scale_sequence_descends_melodic_minor_naturally() {
+        let scale = Scale {
+            start_note: Note::A,
+            mode: ScaleMode::MelodicMinor,
+        };
+        let sequence = scale_sequence(&scale, 3);
+        assert_eq!(sequence.len(), 15);
+        assert_eq!(sequence[6], Pitch::new(Note::Gs, 4));
+        assert_eq!(sequence[8], Pitch::new(Note::G, 4));
+        assert_eq!(sequence.first(), sequence.last());
+    }
+
+    #[test]
+    fn test_collect_pitch_classes() {
+        let messages: [&[u8]; 6] = [
+            &[0x90, 60, 100],
+            &[0x80, 60, 0],
+            &[0x91, 76, 90],
+            &[0x90, 67, 0],
+            &[0x90, 79, 64],
+            &[0xb0, 64, 127],
+        ];
+        let numbers = messages
+            .iter()
+            .filter_map(|message| note_on_number(message));
+        assert_eq!(
+            collect_pitch_classes(numbers),
+            vec![Note::C, Note::E, Note::G]
+        );
+        assert_eq!(
+            collect_pitch_classes([62, 74, 57, 65]),
+            vec![Note::D, Note::F, Note::A]
+        );
+        let report = listen_report(&[Note::C, Note::E, Note::G]);
+        assert!(report.starts_with("PLAYED: C, E, G\nMATCHES: "));
+        assert!(report.contains("C Major"));
+    }
+}
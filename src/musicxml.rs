@@ -0,0 +1,227 @@
+use crate::guitar::Guitar;
+use crate::key::{key_signature, spelled_degrees};
+use crate::note::Note;
+use crate::pitch::Pitch;
+use crate::scale::Scale;
+use itertools::Itertools;
+use strum::EnumCount;
+
+/// quarter notes per measure, the scores are written in 4/4
+const BEATS: usize = 4;
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE score-partwise PUBLIC "-//Recordare//DTD MusicXML 4.0 Partwise//EN" "http://www.musicxml.org/dtds/partwise.dtd">
+<score-partwise version="4.0">
+  <part-list>
+    <score-part id="P1">
+      <part-name>Guitar</part-name>
+    </score-part>
+  </part-list>
+  <part id="P1">
+"#;
+
+/// `step`, `alter` and `octave` of `pitch` written as `name`, where the octave follows the
+/// letter so `Cb` just below C4 is `Cb4`
+fn pitch_elements(name: &str, pitch: Pitch, prefix: &str) -> String {
+    let step = &name[..1];
+    let alter = name.matches('#').count() as i32 - name[1..].matches('b').count() as i32;
+    let octave = (pitch.semitones() - alter).div_euclid(Note::COUNT as i32);
+    let alter = match alter {
+        0 => String::new(),
+        alter => format!("<{prefix}alter>{alter}</{prefix}alter>"),
+    };
+    format!("<{prefix}step>{step}</{prefix}step>{alter}<{prefix}octave>{octave}</{prefix}octave>")
+}
+
+/// one quarter note, with its string (1 being the highest, as MusicXML counts) and fret on
+/// tab staves
+fn note_element(name: &str, pitch: Pitch, tab: Option<(usize, usize)>) -> String {
+    let technical = match tab {
+        Some((string, fret)) => format!(
+            "\n        <notations><technical><string>{string}</string><fret>{fret}</fret></technical></notations>"
+        ),
+        None => String::new(),
+    };
+    format!(
+        "      <note>\n        <pitch>{}</pitch>\n        <duration>1</duration>\n        <type>quarter</type>{technical}\n      </note>\n",
+        pitch_elements(name, pitch, "")
+    )
+}
+
+const REST: &str =
+    "      <note>\n        <rest/>\n        <duration>1</duration>\n        <type>quarter</type>\n      </note>\n";
+
+/// a single part score: `attributes` open the first measure, then the notes four to a
+/// measure, the last one filled up with rests (a single measure of rests without notes)
+fn score(attributes: &str, notes: &[String]) -> String {
+    let chunks = match notes.is_empty() {
+        true => vec![notes],
+        false => notes.chunks(BEATS).collect_vec(),
+    };
+    let measures = chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, notes)| {
+            let attributes = match index {
+                0 => attributes,
+                _ => "",
+            };
+            let rests = REST.repeat(BEATS - notes.len());
+            format!(
+                "    <measure number=\"{}\">\n{attributes}{}{rests}    </measure>\n",
+                index + 1,
+                notes.concat()
+            )
+        })
+        .collect::<String>();
+    format!("{HEADER}{measures}  </part>\n</score-partwise>\n")
+}
+
+/// `<attributes>` for 4/4 with `fifths` sharps (or flats when negative) and `staff` holding
+/// the clef and anything after it
+fn attributes(fifths: i32, staff: &str) -> String {
+    format!(
+        "      <attributes>\n        <divisions>1</divisions>\n        <key><fifths>{fifths}</fifths></key>\n        <time><beats>{BEATS}</beats><beat-type>4</beat-type></time>\n{staff}      </attributes>\n"
+    )
+}
+
+/// a tab staff score of `positions` (zero-based string, fret) as quarter notes, the staff
+/// tuned like `guitar`
+pub fn tab_score(guitar: &Guitar, positions: &[(usize, usize)]) -> String {
+    let open_pitches = guitar.open_pitches();
+    let tuning = open_pitches
+        .iter()
+        .enumerate()
+        .map(|(index, pitch)| {
+            format!(
+                "          <staff-tuning line=\"{}\">{}</staff-tuning>\n",
+                index + 1,
+                pitch_elements(&pitch.note.to_string(), *pitch, "tuning-")
+            )
+        })
+        .collect::<String>();
+    let staff = format!(
+        "        <clef><sign>TAB</sign><line>5</line></clef>\n        <staff-details>\n          <staff-lines>{}</staff-lines>\n{tuning}        </staff-details>\n",
+        guitar.strings.len()
+    );
+    let notes = positions
+        .iter()
+        .map(|&(string, fret)| {
+            let pitch =
+                open_pitches[string].offset_by(guitar.strings[string].semitones_at(fret) as _);
+            let tab = (guitar.strings.len() - string, fret);
+            note_element(&pitch.note.to_string(), pitch, Some(tab))
+        })
+        .collect_vec();
+    score(&attributes(0, &staff), &notes)
+}
+
+/// octave the root of [scale_score] starts in, middle C's
+pub const SCORE_OCTAVE: i32 = 4;
+
+/// the scale ascending from its root to the octave above on a treble staff, with the key
+/// signature of [key_signature] and the degrees spelled as in [spelled_degrees]
+pub fn scale_score(scale: &Scale) -> String {
+    let names = spelled_degrees(scale);
+    let notes = scale
+        .ascending_pitches(SCORE_OCTAVE)
+        .take(names.len() + 1)
+        .zip(names.iter().cycle())
+        .map(|(pitch, name)| note_element(name, pitch, None))
+        .collect_vec();
+    let staff = "        <clef><sign>G</sign><line>2</line></clef>\n";
+    score(&attributes(key_signature(scale).fifths, staff), &notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guitar::Tuning;
+
+    /// whether every tag is closed in order, skipping the declaration and the doctype
+    fn well_formed(xml: &str) -> bool {
+        let mut open = vec![];
+        for tag in xml
+            .split('<')
+            .skip(1)
+            .map(|rest| rest.split('>').next().unwrap())
+        {
+            match tag.chars().next() {
+                Some('?' | '!') => {}
+                _ if tag.ends_with('/') => {}
+                Some('/') => match open.pop() {
+                    Some(name) if name == tag[1..] => {}
+                    _ => return false,
+                },
+                _ => open.push(tag.split_whitespace().next().unwrap().to_string()),
+            }
+        }
+        open.is_empty()
+    }
+
+    #[test]
+    fn test_tab_score() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let positions = guitar
+            .transcribe(&[Note::C, Note::E, Note::G, Note::C, Note::E], 12)
+            .unwrap();
+        let xml = tab_score(&guitar, &positions);
+        assert!(well_formed(&xml));
+        assert!(!well_formed("<a><b></a></b>"));
+        assert_eq!(xml.matches("<pitch>").count(), 5);
+        assert_eq!(xml.matches("<rest/>").count(), 3);
+        assert_eq!(xml.matches("<measure ").count(), 2);
+        assert_eq!(xml.matches("<staff-tuning ").count(), 6);
+        assert!(xml.contains("<staff-tuning line=\"1\"><tuning-step>E</tuning-step><tuning-octave>2</tuning-octave></staff-tuning>"));
+        let (string, fret) = positions[0];
+        assert!(xml.contains(&format!(
+            "<string>{}</string><fret>{fret}</fret>",
+            6 - string
+        )));
+        assert!(xml.contains("<pitch><step>C</step><octave>3</octave></pitch>"));
+        let empty = tab_score(&guitar, &[]);
+        assert!(well_formed(&empty));
+        assert_eq!(empty.matches("<measure ").count(), 1);
+        assert_eq!(empty.matches("<rest/>").count(), BEATS);
+        assert!(empty.contains("<attributes>"));
+    }
+
+    #[test]
+    fn test_pitch_spelling() {
+        let b3 = Pitch::new(Note::B, 3);
+        assert_eq!(
+            pitch_elements("Cb", b3, ""),
+            "<step>C</step><alter>-1</alter><octave>4</octave>"
+        );
+        assert_eq!(
+            pitch_elements("F#", Pitch::new(Note::Fs, 4), ""),
+            "<step>F</step><alter>1</alter><octave>4</octave>"
+        );
+    }
+
+    #[test]
+    fn test_scale_score() {
+        let scale = |s: &str| s.parse::<Scale>().unwrap();
+        let xml = scale_score(&scale("D major"));
+        assert!(well_formed(&xml));
+        assert!(xml.contains("<key><fifths>2</fifths></key>"));
+        let pitches = xml
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("<pitch>"))
+            .collect_vec();
+        assert_eq!(pitches.len(), 8);
+        assert_eq!(pitches[0], "<step>D</step><octave>4</octave></pitch>");
+        assert_eq!(
+            pitches[2],
+            "<step>F</step><alter>1</alter><octave>4</octave></pitch>"
+        );
+        assert_eq!(
+            pitches[6],
+            "<step>C</step><alter>1</alter><octave>5</octave></pitch>"
+        );
+        assert_eq!(pitches[7], "<step>D</step><octave>5</octave></pitch>");
+        let xml = scale_score(&scale("F major"));
+        assert!(xml.contains("<key><fifths>-1</fifths></key>"));
+        assert!(xml.contains("<step>B</step><alter>-1</alter><octave>4</octave>"));
+    }
+}
@@ -0,0 +1,504 @@
+use clap::ValueEnum;
+use derive_more::{Display, Error};
+use itertools::Itertools;
+use std::str::FromStr;
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
+
+#[derive(
+    Debug, EnumIter, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumCount, Hash, ValueEnum,
+)]
+pub enum Note {
+    C,
+    Cs,
+    D,
+    Ds,
+    E,
+    F,
+    Fs,
+    G,
+    Gs,
+    A,
+    As,
+    B,
+}
+
+impl std::fmt::Display for Note {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            Note::C => "C",
+            Note::Cs => "C#",
+            Note::D => "D",
+            Note::Ds => "D#",
+            Note::E => "E",
+            Note::F => "F",
+            Note::Fs => "F#",
+            Note::G => "G",
+            Note::Gs => "G#",
+            Note::A => "A",
+            Note::As => "A#",
+            Note::B => "B",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+impl Note {
+    fn cycle() -> impl Iterator<Item = Self> {
+        Self::iter().cycle()
+    }
+
+    pub fn cycle_from(self) -> impl Iterator<Item = Self> {
+        Self::cycle().skip_while(move |i| i != &self)
+    }
+
+    pub fn offset_by(self, offset: i32) -> Self {
+        let offset = offset.wrapping_rem_euclid(Self::COUNT as i32);
+        self.cycle_from()
+            .nth(offset as _)
+            .expect("this is an infinite stream, come on")
+    }
+
+    /// common spellings of the pitch class, the one used by `Display` first
+    pub fn enharmonic_names(self) -> Vec<&'static str> {
+        match self {
+            Note::C => vec!["C", "B#"],
+            Note::Cs => vec!["C#", "Db"],
+            Note::D => vec!["D"],
+            Note::Ds => vec!["D#", "Eb"],
+            Note::E => vec!["E", "Fb"],
+            Note::F => vec!["F", "E#"],
+            Note::Fs => vec!["F#", "Gb"],
+            Note::G => vec!["G"],
+            Note::Gs => vec!["G#", "Ab"],
+            Note::A => vec!["A"],
+            Note::As => vec!["A#", "Bb"],
+            Note::B => vec!["B", "Cb"],
+        }
+    }
+
+    /// `Db` rather than `C#` for the black keys, the natural name otherwise
+    pub fn flat_name(self) -> &'static str {
+        match self.to_string().ends_with('#') {
+            true => self.enharmonic_names()[1],
+            false => self.enharmonic_names()[0],
+        }
+    }
+
+    /// fixed do syllable, C is always Do and sharps keep the `#`
+    pub fn fixed_do(self) -> &'static str {
+        match self {
+            Note::C => "Do",
+            Note::Cs => "Do#",
+            Note::D => "Re",
+            Note::Ds => "Re#",
+            Note::E => "Mi",
+            Note::F => "Fa",
+            Note::Fs => "Fa#",
+            Note::G => "Sol",
+            Note::Gs => "Sol#",
+            Note::A => "La",
+            Note::As => "La#",
+            Note::B => "Si",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// semitones going up from this note to `other`, 0 to 11
+    pub fn ascending_distance(self, other: Note) -> usize {
+        (other.index() + Self::COUNT - self.index()) % Self::COUNT
+    }
+
+    /// semitones going down from this note to `other`, 0 to 11
+    pub fn descending_distance(self, other: Note) -> usize {
+        other.ascending_distance(self)
+    }
+
+    /// steps clockwise from C around the circle of fifths: 0 for C, 1 for G, 11 for F
+    pub fn fifths_from_c(self) -> usize {
+        self.index() * 7 % Self::COUNT
+    }
+
+    /// the note as many semitones below `axis` as this one is above it
+    pub fn mirror(self, axis: Note) -> Self {
+        Self::from_index(2 * axis.index() + Self::COUNT - self.index())
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::iter()
+            .nth(index % Self::COUNT)
+            .expect("index is reduced modulo the note count")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(fmt = "invalid note `{}`{}", input, "suggestion_hint(input)")]
+pub struct ParseNoteError {
+    #[error(not(source))]
+    pub input: String,
+}
+
+fn natural(letter: char) -> Option<Note> {
+    match letter.to_ascii_uppercase() {
+        'C' => Some(Note::C),
+        'D' => Some(Note::D),
+        'E' => Some(Note::E),
+        'F' => Some(Note::F),
+        'G' => Some(Note::G),
+        'A' => Some(Note::A),
+        'B' => Some(Note::B),
+        _ => None,
+    }
+}
+
+/// parses a leading note name with `#`/`b` accidentals, returning the unparsed rest
+pub fn split_note_prefix(input: &str) -> Option<(Note, &str)> {
+    let mut chars = input.chars();
+    let note = natural(chars.next()?)?;
+    Some(apply_accidentals(note, chars.as_str()))
+}
+
+/// raises or lowers `note` by any leading `#`/`b` of `rest`, returning what follows them
+fn apply_accidentals(mut note: Note, mut rest: &str) -> (Note, &str) {
+    loop {
+        let offset = match rest.chars().next() {
+            Some('#') => 1,
+            Some('b') => -1,
+            _ => break,
+        };
+        note = note.offset_by(offset);
+        rest = &rest[1..];
+    }
+    (note, rest)
+}
+
+/// the naming convention note arguments are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum NoteInput {
+    /// `C`, `F#`, `Bb`
+    #[default]
+    English,
+    /// `C`, `Fis`, `B` for Bb and `H` for B
+    German,
+    /// `Do`, `Fa#`, `Sib`
+    Italian,
+}
+
+/// German letters, `H` being B and `B` being Bb, each `is` raising and each `es` lowering
+/// (shortened to `s` after `A` and `E`, as in `As` and `Es`)
+fn parse_german(input: &str) -> Option<Note> {
+    let input = input.to_ascii_lowercase();
+    let mut chars = input.chars();
+    let letter = chars.next()?;
+    let mut note = match letter {
+        'h' => Note::B,
+        'b' => Note::As,
+        other => natural(other)?,
+    };
+    let mut rest = chars.as_str();
+    if matches!(letter, 'a' | 'e') && rest.starts_with('s') {
+        note = note.offset_by(-1);
+        rest = &rest[1..];
+    }
+    while !rest.is_empty() {
+        let (offset, suffix) = match rest {
+            _ if rest.starts_with("is") => (1, "is"),
+            _ if rest.starts_with("es") => (-1, "es"),
+            _ => return None,
+        };
+        note = note.offset_by(offset);
+        rest = &rest[suffix.len()..];
+    }
+    Some(note)
+}
+
+/// fixed do syllables with `#`/`b` accidentals
+fn parse_italian(input: &str) -> Option<Note> {
+    let (syllable, natural) = Note::iter()
+        .filter(|note| !note.to_string().ends_with('#'))
+        .map(|note| (note.fixed_do(), note))
+        .find(|(syllable, _)| {
+            input
+                .get(..syllable.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(syllable))
+        })?;
+    match apply_accidentals(natural, &input[syllable.len()..]) {
+        (note, "") => Some(note),
+        _ => None,
+    }
+}
+
+impl NoteInput {
+    /// reads one note name written in this convention
+    pub fn parse(self, input: &str) -> Result<Note, ParseNoteError> {
+        let note = match self {
+            NoteInput::English => return input.parse(),
+            NoteInput::German => parse_german(input.trim()),
+            NoteInput::Italian => parse_italian(input.trim()),
+        };
+        note.ok_or_else(|| ParseNoteError {
+            input: input.to_string(),
+        })
+    }
+}
+
+/// notes as written in a comma separated list such as `C,E,G,Fb`, in order and with any
+/// enharmonic repeats
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteSet {
+    pub entries: Vec<(String, Note)>,
+}
+
+impl FromStr for NoteSet {
+    type Err = ParseNoteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, NoteInput::English)
+    }
+}
+
+impl NoteSet {
+    /// a comma separated list with every note written in the `notes` convention
+    pub fn parse_with(input: &str, notes: NoteInput) -> Result<Self, ParseNoteError> {
+        let entries = input
+            .split(',')
+            .map(|name| Ok((name.trim().to_string(), notes.parse(name)?)))
+            .collect::<Result<_, ParseNoteError>>()?;
+        Ok(Self { entries })
+    }
+
+    /// every entry naming a note already listed before it, such as `Fb` after `E`, with the
+    /// name it was first written as
+    pub fn duplicates(&self) -> Vec<(&str, &str)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (name, note))| {
+                let (first, _) = self.entries[..index]
+                    .iter()
+                    .find(|(_, earlier)| earlier == note)?;
+                Some((name.as_str(), first.as_str()))
+            })
+            .collect()
+    }
+
+    /// the notes with every repeat left out, in order of first appearance
+    pub fn simplified(&self) -> Vec<Note> {
+        self.entries
+            .iter()
+            .map(|(_, note)| *note)
+            .unique()
+            .collect()
+    }
+}
+
+/// `G:C` into its two notes written in the `notes` convention, the span going up from the
+/// first to the second
+pub fn parse_note_span(input: &str, notes: NoteInput) -> Result<(Note, Note), ParseNoteError> {
+    let (low, high) = input.split_once(':').ok_or_else(|| ParseNoteError {
+        input: input.to_string(),
+    })?;
+    Ok((notes.parse(low)?, notes.parse(high)?))
+}
+
+/// the note probably meant by a spelling from another notation tradition, such as the
+/// German `H` for B or the `♯`/`♭` signs
+pub fn suggest_note(input: &str) -> Option<Note> {
+    let input = input.trim().replace('♯', "#").replace('♭', "b");
+    let input = match input.strip_prefix(['H', 'h']) {
+        Some(rest) => format!("B{rest}"),
+        None => input,
+    };
+    input.parse().ok()
+}
+
+fn suggestion_hint(input: &str) -> String {
+    match suggest_note(input) {
+        Some(note) => format!(", did you mean {note}?"),
+        None => String::new(),
+    }
+}
+
+impl FromStr for Note {
+    type Err = ParseNoteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseNoteError {
+            input: s.to_string(),
+        };
+        let (note, rest) = split_note_prefix(s.trim()).ok_or_else(error)?;
+        match rest {
+            "" => Ok(note),
+            "s" => Ok(note.offset_by(1)),
+            _ => Err(error()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Note {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Note {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+pub fn note_bitmask(notes: impl IntoIterator<Item = Note>) -> u16 {
+    notes
+        .into_iter()
+        .fold(0, |mask, note| mask | (1 << note.index()))
+}
+
+/// the notes set in a [note_bitmask], from C upwards
+pub fn notes_of_bitmask(mask: u16) -> Vec<Note> {
+    Note::iter()
+        .filter(|note| mask & (1 << note.index()) != 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(Note::C.offset_by(1), Note::Cs);
+        assert_eq!(Note::C.offset_by(-1), Note::B);
+    }
+
+    #[test]
+    fn test_directional_distance() {
+        assert_eq!(Note::C.ascending_distance(Note::A), 9);
+        assert_eq!(Note::C.descending_distance(Note::A), 3);
+        assert_eq!(Note::A.ascending_distance(Note::C), 3);
+        assert_eq!(Note::E.ascending_distance(Note::E), 0);
+        assert_eq!(Note::E.descending_distance(Note::E), 0);
+        for (a, b) in Note::iter().cartesian_product(Note::iter()) {
+            assert_eq!(a.offset_by(a.ascending_distance(b) as i32), b);
+            assert_eq!(a.offset_by(-(a.descending_distance(b) as i32)), b);
+        }
+    }
+
+    #[test]
+    fn test_parse_note() {
+        assert_eq!("C".parse(), Ok(Note::C));
+        assert_eq!("f#".parse(), Ok(Note::Fs));
+        assert_eq!("Cs".parse(), Ok(Note::Cs));
+        assert_eq!("Db".parse(), Ok(Note::Cs));
+        assert_eq!("Cb".parse(), Ok(Note::B));
+        assert!("H".parse::<Note>().is_err());
+        assert!("C#m".parse::<Note>().is_err());
+        assert_eq!(split_note_prefix("Bbm7"), Some((Note::As, "m7")));
+    }
+
+    #[test]
+    fn test_enharmonic_names() {
+        assert_eq!(Note::Cs.enharmonic_names(), vec!["C#", "Db"]);
+        assert_eq!(Note::E.enharmonic_names(), vec!["E", "Fb"]);
+        for note in Note::iter() {
+            let names = note.enharmonic_names();
+            assert_eq!(names[0], note.to_string());
+            assert!(names.iter().all(|name| name.parse() == Ok(note)));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        for note in Note::iter() {
+            let json = serde_json::to_string(&note).unwrap();
+            assert_eq!(json, format!("\"{note}\""));
+            assert_eq!(serde_json::from_str::<Note>(&json).unwrap(), note);
+        }
+        assert_eq!(serde_json::from_str::<Note>("\"Cs\"").unwrap(), Note::Cs);
+        assert!(serde_json::from_str::<Note>("\"H\"").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_offset_matches_index_arithmetic(
+            index in 0..Note::COUNT,
+            offset in -10_000i32..10_000,
+        ) {
+            let note = Note::from_index(index);
+            let expected = (note.index() as i32 + offset).rem_euclid(Note::COUNT as i32);
+            let expected = Note::from_index(expected as usize);
+            prop_assert_eq!(note.offset_by(offset), expected);
+        }
+
+        #[test]
+        fn test_index_roundtrip(index in 0..Note::COUNT) {
+            prop_assert_eq!(Note::from_index(index).index(), index);
+        }
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let error = "H".parse::<Note>().unwrap_err();
+        assert_eq!(error.to_string(), "invalid note `H`, did you mean B?");
+        assert_eq!(suggest_note("h"), Some(Note::B));
+        assert!("C♯".parse::<Note>().is_err());
+        assert_eq!(suggest_note("C♯"), Some(Note::Cs));
+        let error = "X".parse::<Note>().unwrap_err();
+        assert_eq!(error.to_string(), "invalid note `X`");
+    }
+
+    #[test]
+    fn test_note_span() {
+        let english = NoteInput::English;
+        assert_eq!(parse_note_span("G:C", english), Ok((Note::G, Note::C)));
+        assert!(parse_note_span("G", english).is_err());
+        assert!(parse_note_span("G:H", english).is_err());
+        assert_eq!(
+            parse_note_span("G:H", NoteInput::German),
+            Ok((Note::G, Note::B))
+        );
+    }
+
+    #[test]
+    fn test_flat_name() {
+        assert_eq!(Note::Cs.flat_name(), "Db");
+        assert_eq!(Note::As.flat_name(), "Bb");
+        assert_eq!(Note::E.flat_name(), "E");
+    }
+
+    #[test]
+    fn test_note_set() {
+        let set: NoteSet = "C,E,G,Fb".parse().unwrap();
+        assert_eq!(set.entries.len(), 4);
+        assert_eq!(set.simplified(), [Note::C, Note::E, Note::G]);
+        assert_eq!(set.duplicates(), [("Fb", "E")]);
+        let set: NoteSet = "A, C, E".parse().unwrap();
+        assert!(set.duplicates().is_empty());
+        assert!("C,E,X".parse::<NoteSet>().is_err());
+    }
+
+    #[test]
+    fn test_note_input_conventions() {
+        let german = |name| NoteInput::German.parse(name);
+        assert_eq!(german("H"), Ok(Note::B));
+        assert_eq!(german("B"), Ok(Note::As));
+        assert_eq!(german("Fis"), Ok(Note::Fs));
+        assert_eq!(german("es"), Ok(Note::Ds));
+        assert_eq!(german("As"), Ok(Note::Gs));
+        assert_eq!(german("Ceses"), Ok(Note::As));
+        assert!(german("F#").is_err());
+        let italian = |name| NoteInput::Italian.parse(name);
+        assert_eq!(italian("Do"), Ok(Note::C));
+        assert_eq!(italian("sol#"), Ok(Note::Gs));
+        assert_eq!(italian("Sib"), Ok(Note::As));
+        assert!(italian("So").is_err());
+        assert_eq!(NoteInput::English.parse("Bb"), Ok(Note::As));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::note::{split_note_prefix, Note};
+use clap::ValueEnum;
+use derive_more::{Display, Error};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use strum::EnumCount;
+
+/// how octaves are written next to a note name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OctaveNotation {
+    /// `C4` for middle C
+    #[default]
+    Scientific,
+    /// `c′` for middle C, `c` for the octave below, `C` and `C,` further down
+    Helmholtz,
+}
+
+/// a note at a concrete octave, in scientific pitch notation (middle C is C4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pitch {
+    pub octave: i32,
+    pub note: Note,
+}
+
+impl std::fmt::Display for Pitch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.note, self.octave)
+    }
+}
+
+impl Pitch {
+    pub fn new(note: Note, octave: i32) -> Self {
+        Self { octave, note }
+    }
+
+    /// the pitch written in `notation`
+    pub fn name(self, notation: OctaveNotation) -> String {
+        self.name_from(notation, Note::C)
+    }
+
+    /// the octave number when octaves start at `boundary` rather than at C, so with A the
+    /// B and the C above A3 are still in octave 3
+    pub fn octave_from(self, boundary: Note) -> i32 {
+        (self.semitones() - boundary.index() as i32).div_euclid(Note::COUNT as i32)
+    }
+
+    /// [Pitch::name] with octaves starting at `boundary`
+    pub fn name_from(self, notation: OctaveNotation, boundary: Note) -> String {
+        let octave = self.octave_from(boundary);
+        match notation {
+            OctaveNotation::Scientific => format!("{}{octave}", self.note),
+            OctaveNotation::Helmholtz => match octave {
+                octave if octave >= 3 => format!(
+                    "{}{}",
+                    self.note.to_string().to_lowercase(),
+                    "′".repeat((octave - 3) as usize)
+                ),
+                octave => format!("{}{}", self.note, ",".repeat((2 - octave) as usize)),
+            },
+        }
+    }
+
+    /// semitones above C0
+    pub fn semitones(self) -> i32 {
+        self.octave * Note::COUNT as i32 + self.note.index() as i32
+    }
+
+    pub fn from_semitones(semitones: i32) -> Self {
+        let count = Note::COUNT as i32;
+        Self {
+            octave: semitones.div_euclid(count),
+            note: Note::from_index(semitones.rem_euclid(count) as usize),
+        }
+    }
+
+    pub fn offset_by(self, offset: i32) -> Self {
+        Self::from_semitones(self.semitones() + offset)
+    }
+
+    pub fn midi_number(self) -> i32 {
+        self.semitones() + Note::COUNT as i32
+    }
+
+    pub fn from_midi_number(number: i32) -> Self {
+        Self::from_semitones(number - Note::COUNT as i32)
+    }
+
+    /// equal tempered frequency in Hz with A4 at [A4_FREQUENCY]; whole octaves are applied
+    /// as exact powers of two, so a pitch an octave up has exactly double the frequency
+    pub fn frequency(self) -> f64 {
+        self.frequency_at(A4_FREQUENCY)
+    }
+
+    /// like [Pitch::frequency], tuned with A4 at `a4` Hz instead
+    pub fn frequency_at(self, a4: f64) -> f64 {
+        let count = Note::COUNT as i32;
+        let from_a4 = self.midi_number() - Pitch::new(Note::A, 4).midi_number();
+        let octaves = from_a4.div_euclid(count);
+        let semitones = from_a4.rem_euclid(count);
+        a4 * 2f64.powf(semitones as f64 / count as f64) * 2f64.powi(octaves)
+    }
+}
+
+pub const A4_FREQUENCY: f64 = 440.0;
+
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(
+    fmt = "invalid pitch `{}`, expected a note and an octave such as `E2`",
+    input
+)]
+pub struct ParsePitchError {
+    #[error(not(source))]
+    pub input: String,
+}
+
+impl FromStr for Pitch {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParsePitchError {
+            input: s.to_string(),
+        };
+        let (note, octave) = split_note_prefix(s.trim()).ok_or_else(error)?;
+        let octave = octave.parse().map_err(|_| error())?;
+        Ok(Self::new(note, octave))
+    }
+}
+
+/// `E2:A4` into the pitches from E2 up to A4, both included
+pub fn parse_pitch_range(input: &str) -> Result<RangeInclusive<Pitch>, ParsePitchError> {
+    let error = || ParsePitchError {
+        input: input.to_string(),
+    };
+    let (low, high) = input.split_once(':').ok_or_else(error)?;
+    let (low, high) = (low.parse::<Pitch>()?, high.parse::<Pitch>()?);
+    match low <= high {
+        true => Ok(low..=high),
+        false => Err(error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pitch_arithmetic() {
+        let middle_c = Pitch::new(Note::C, 4);
+        assert_eq!(middle_c.midi_number(), 60);
+        assert_eq!(Pitch::from_midi_number(69), Pitch::new(Note::A, 4));
+        assert_eq!(middle_c.offset_by(-1), Pitch::new(Note::B, 3));
+        assert_eq!(middle_c.offset_by(12), Pitch::new(Note::C, 5));
+        assert!(Pitch::new(Note::B, 3) < middle_c);
+    }
+
+    #[test]
+    fn test_frequency() {
+        assert_eq!(Pitch::new(Note::A, 4).frequency(), 440.0);
+        assert_eq!(Pitch::new(Note::A, 2).frequency(), 110.0);
+        let middle_c = Pitch::new(Note::C, 4).frequency();
+        assert!((middle_c - 261.6256).abs() < 1e-3);
+        assert_eq!(Pitch::new(Note::C, 5).frequency(), middle_c * 2.0);
+        assert_eq!(Pitch::new(Note::A, 4).frequency_at(442.0), 442.0);
+        assert_eq!(Pitch::new(Note::A, 3).frequency_at(432.0), 216.0);
+        let low_e = Pitch::new(Note::E, 2).frequency_at(432.0);
+        assert!((low_e - 432.0 * 2f64.powf(-29.0 / 12.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_pitch() {
+        assert_eq!("E2".parse(), Ok(Pitch::new(Note::E, 2)));
+        assert_eq!("Bb3".parse(), Ok(Pitch::new(Note::As, 3)));
+        assert_eq!("C-1".parse(), Ok(Pitch::new(Note::C, -1)));
+        assert!("E".parse::<Pitch>().is_err());
+        assert_eq!(
+            parse_pitch_range("E2:A4"),
+            Ok(Pitch::new(Note::E, 2)..=Pitch::new(Note::A, 4))
+        );
+        assert!(parse_pitch_range("A4:E2").is_err());
+        assert!(parse_pitch_range("E2").is_err());
+    }
+
+    #[test]
+    fn test_octave_notation() {
+        let middle_c = Pitch::new(Note::C, 4);
+        assert_eq!(middle_c.name(OctaveNotation::Scientific), "C4");
+        assert_eq!(middle_c.name(OctaveNotation::Helmholtz), "c′");
+        let helmholtz = |note, octave| Pitch::new(note, octave).name(OctaveNotation::Helmholtz);
+        assert_eq!(helmholtz(Note::C, 3), "c");
+        assert_eq!(helmholtz(Note::Fs, 5), "f#′′");
+        assert_eq!(helmholtz(Note::E, 2), "E");
+        assert_eq!(helmholtz(Note::A, 0), "A,,");
+    }
+
+    #[test]
+    fn test_octave_boundary() {
+        let a3 = Pitch::new(Note::A, 3);
+        let b3 = Pitch::new(Note::B, 3);
+        let c4 = Pitch::new(Note::C, 4);
+        assert_eq!(a3.octave_from(Note::A), b3.octave_from(Note::A));
+        assert_eq!(c4.octave_from(Note::A), 3);
+        assert_eq!(Pitch::new(Note::A, 4).octave_from(Note::A), 4);
+        assert_eq!(Pitch::new(Note::Gs, 4).octave_from(Note::A), 3);
+        assert_eq!(c4.octave_from(Note::C), 4);
+        assert_eq!(b3.name_from(OctaveNotation::Scientific, Note::A), "B3");
+        assert_eq!(c4.name_from(OctaveNotation::Scientific, Note::A), "C3");
+        assert_eq!(c4.name_from(OctaveNotation::Helmholtz, Note::A), "c");
+    }
+}
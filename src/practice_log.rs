@@ -0,0 +1,84 @@
+use crate::chord::Chord;
+use crate::guitar::Tuning;
+use crate::scale::Scale;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// one rendered neck, a line of the practice log
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PracticeLogEntry {
+    /// seconds since the unix epoch
+    pub timestamp: u64,
+    pub scale: Scale,
+    pub tuning: Tuning,
+    /// the chord symbol, such as `Am7`
+    pub chord: Option<String>,
+}
+
+impl PracticeLogEntry {
+    /// an entry stamped with the current time
+    pub fn now(scale: Scale, tuning: Tuning, chord: Option<&Chord>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self {
+            timestamp,
+            scale,
+            tuning,
+            chord: chord.map(Chord::to_string),
+        }
+    }
+}
+
+/// appends `entry` as a single JSON line to `path`, creating the file if needed
+pub fn append_entry(path: &Path, entry: &PracticeLogEntry) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    writeln!(file, "{line}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_entries() {
+        let path = std::env::temp_dir().join(format!(
+            "infinity-board-practice-log-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let scale: Scale = "A dorian".parse().unwrap();
+        let chord: Chord = "Am7".parse().unwrap();
+        let first = PracticeLogEntry::now(scale, Tuning::Standard, None);
+        let second = PracticeLogEntry::now(scale, Tuning::Standard, Some(&chord));
+        append_entry(&path, &first).unwrap();
+        append_entry(&path, &second).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries = contents
+            .lines()
+            .map(|line| serde_json::from_str::<PracticeLogEntry>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [first, second]);
+        assert_eq!(entries[1].chord.as_deref(), Some("Am7"));
+        assert!(contents.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_entry_json() {
+        use crate::note::Note::*;
+        let entry = PracticeLogEntry {
+            timestamp: 0,
+            scale: "F# melodic-minor".parse().unwrap(),
+            tuning: Tuning::Custom(vec![D, A, D, G, A, D]),
+            chord: Some("F#m7".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_string(&entry).unwrap(),
+            r#"{"timestamp":0,"scale":{"start_note":"F#","mode":"melodic-minor"},"tuning":{"custom":["D","A","D","G","A","D"]},"chord":"F#m7"}"#
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// a deterministic generator for a given seed, seeded from the clock otherwise
+pub fn rng_from_seed(seed: Option<u64>) -> StdRng {
+    let seed = seed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+    StdRng::seed_from_u64(seed)
+}
@@ -0,0 +1,127 @@
+use crate::guitar::Guitar;
+use crate::layout::Layout;
+use crate::render::{
+    fret_range, RenderOptions, BASS_COLOR, CHORD_COLOR, HIGHLIGHT_COLOR, ROOT_COLOR, TENSION_COLOR,
+};
+use crate::scale::Scale;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Rect, Transform};
+
+pub const FRET_WIDTH: u32 = 40;
+pub const STRING_SPACING: u32 = 24;
+pub const MARGIN: u32 = 20;
+const DOT_RADIUS: f32 = 8.0;
+const LINE_WIDTH: f32 = 2.0;
+
+/// the terminal colors used by the text renderer as RGB
+fn rgb(color: Option<&str>) -> (u8, u8, u8) {
+    match color {
+        Some("91") => (220, 50, 47),
+        Some(CHORD_COLOR) => (40, 160, 40),
+        Some(ROOT_COLOR) => (230, 180, 0),
+        Some("94") => (38, 110, 210),
+        Some(BASS_COLOR) => (190, 60, 190),
+        Some(HIGHLIGHT_COLOR) => (0, 170, 190),
+        Some(TENSION_COLOR) => (200, 110, 30),
+        Some(_) => (130, 130, 130),
+        None => (30, 30, 30),
+    }
+}
+
+fn paint(color: Option<&str>) -> Paint<'static> {
+    let (r, g, b) = rgb(color);
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, 255);
+    paint.anti_alias = true;
+    paint
+}
+
+fn fill_rect(pixmap: &mut Pixmap, rect: Option<Rect>, color: Option<&str>) {
+    if let Some(rect) = rect {
+        pixmap.fill_rect(rect, &paint(color), Transform::identity(), None);
+    }
+}
+
+/// width and height in pixels: one column per shown fret, one line per string
+pub fn png_size(guitar: &Guitar, options: &RenderOptions) -> (u32, u32) {
+    let frets = fret_range(guitar, options).len() as u32;
+    let strings = guitar.strings.len().max(1) as u32;
+    (
+        MARGIN * 2 + frets * FRET_WIDTH,
+        MARGIN * 2 + (strings - 1) * STRING_SPACING,
+    )
+}
+
+/// the landscape neck as a PNG, highest string on top, with a dot in the cell colors
+/// wherever the text renderer would print a note
+pub fn render_png(guitar: &Guitar, scale: &Scale, options: &RenderOptions) -> Vec<u8> {
+    let (width, height) = png_size(guitar, options);
+    let mut pixmap = Pixmap::new(width, height).expect("the margins keep the image non-empty");
+    pixmap.fill(tiny_skia::Color::WHITE);
+    let layout = Layout::new(guitar, scale, options);
+    let frets = layout.frets.len();
+    let neck_width = (frets as u32 * FRET_WIDTH) as f32;
+    let neck_height = ((layout.strings.len().max(1) - 1) as u32 * STRING_SPACING) as f32;
+    let (left, top) = (MARGIN as f32, MARGIN as f32);
+    for fret in 0..=frets {
+        let x = left + (fret as u32 * FRET_WIDTH) as f32 - LINE_WIDTH / 2.0;
+        let rect = Rect::from_xywh(x, top, LINE_WIDTH, neck_height.max(LINE_WIDTH));
+        fill_rect(&mut pixmap, rect, Some("37"));
+    }
+    for (row, string) in layout.strings.iter().rev().enumerate() {
+        let y = top + (row as u32 * STRING_SPACING) as f32;
+        let rect = Rect::from_xywh(left, y - LINE_WIDTH / 2.0, neck_width, LINE_WIDTH);
+        fill_rect(&mut pixmap, rect, None);
+        for (column, cell) in string.cells.iter().enumerate() {
+            if cell.label.is_none() {
+                continue;
+            }
+            let x = left + (column as f32 + 0.5) * FRET_WIDTH as f32;
+            if let Some(circle) = PathBuilder::from_circle(x, y, DOT_RADIUS) {
+                let paint = paint(cell.color);
+                pixmap.fill_path(
+                    &circle,
+                    &paint,
+                    FillRule::Winding,
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+    }
+    pixmap.encode_png().expect("encoding an in-memory png")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guitar::Tuning;
+    use crate::note::Note;
+    use crate::scale::ScaleMode;
+
+    #[test]
+    fn test_png_dimensions() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = RenderOptions::default();
+        let path =
+            std::env::temp_dir().join(format!("infinity-board-neck-{}.png", std::process::id()));
+        std::fs::write(&path, render_png(&guitar, &scale, &options)).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&bytes[12..16], b"IHDR");
+        let width = u32::from_be_bytes(bytes[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(bytes[20..24].try_into().unwrap());
+        assert_eq!(
+            (width, height),
+            (
+                MARGIN * 2 + 13 * FRET_WIDTH,
+                MARGIN * 2 + 5 * STRING_SPACING
+            )
+        );
+        assert_eq!((width, height), png_size(&guitar, &options));
+    }
+}
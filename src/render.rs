@@ -0,0 +1,1428 @@
+use crate::chord::{Chord, TriadOverlay};
+use crate::guitar::Guitar;
+use crate::key::{plural, spell_note};
+use crate::layout::Layout;
+use crate::note::{Note, NoteInput};
+use crate::pitch::Pitch;
+use crate::scale::Scale;
+use clap::ValueEnum;
+use derive_more::{Display, Error};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Range, RangeInclusive};
+use strum::EnumCount;
+
+pub const ROOT_COLOR: &str = "93";
+pub const HIGHLIGHT_COLOR: &str = "96";
+pub const CHORD_COLOR: &str = "92";
+pub const BASS_COLOR: &str = "95";
+pub const TENSION_COLOR: &str = "33";
+pub const EMPTY_CELL: &str = "|";
+/// one color per scale degree, the first matching the root color
+pub const DEGREE_COLORS: [&str; 7] = ["93", "91", "92", "94", "95", "96", "37"];
+/// the bebop passing note, which has no degree color of its own
+pub const PASSING_COLOR: &str = "90";
+
+/// named terminal colors for --note-colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    pub fn code(self) -> &'static str {
+        match self {
+            Color::Red => "91",
+            Color::Green => "92",
+            Color::Yellow => "93",
+            Color::Blue => "94",
+            Color::Magenta => "95",
+            Color::Cyan => "96",
+            Color::White => "97",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(
+    fmt = "invalid note colors `{}`, expected pairs such as `C=red,G=green`",
+    input
+)]
+pub struct ParseNoteColorsError {
+    #[error(not(source))]
+    pub input: String,
+}
+
+/// `C=red,G=green`, the notes written in the `notes` convention, into a color per pitch class,
+/// later pairs winning
+pub fn parse_note_colors(
+    input: &str,
+    notes: NoteInput,
+) -> Result<HashMap<Note, Color>, ParseNoteColorsError> {
+    let error = || ParseNoteColorsError {
+        input: input.to_string(),
+    };
+    input
+        .split(',')
+        .map(|pair| {
+            let (note, color) = pair.split_once('=').ok_or_else(error)?;
+            let note = notes.parse(note).map_err(|_| error())?;
+            let color = Color::from_str(color.trim(), true).map_err(|_| error())?;
+            Ok((note, color))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Orientation {
+    Landscape,
+    Portrait,
+}
+
+/// what a scale note cell shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Labels {
+    /// note names, or `O` without --all-note-names
+    Names,
+    /// movable do syllables relative to the root
+    Solfege,
+    /// fixed do syllables, C is Do in every key
+    SolfegeFixed,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub all_note_names: bool,
+    pub labels: Labels,
+    pub frets_start: usize,
+    pub orientation: Orientation,
+    pub separator: String,
+    pub fixed_width: bool,
+    pub highlight: HashSet<Note>,
+    /// string numbers (1 being the lowest) left out of the neck
+    pub skip_strings: HashSet<usize>,
+    /// when set, only these (zero-based string, fret) positions are marked
+    pub only_positions: Option<HashSet<(usize, usize)>>,
+    pub chord: Option<Chord>,
+    /// available tensions of the chord, colored apart from its tones
+    pub tensions: Vec<Note>,
+    pub triad_overlay: Option<TriadOverlay>,
+    /// skip the open string column even when the window starts at fret 0
+    pub no_open: bool,
+    /// add a header row holding the fret numbers
+    pub fret_numbers: bool,
+    /// added to the printed fret numbers only, the cells are not moved
+    pub fret_number_offset: i32,
+    /// draw the (landscape) neck inside a box with the scale name in the title bar
+    pub fancy: bool,
+    /// mark only the root, telling the lowest root on the neck apart from its octaves
+    pub only_root_octaves: bool,
+    /// split the neck into blocks of this many frets, each with its own fret numbers
+    pub wrap: Option<usize>,
+    /// pitches outside this range are left empty
+    pub range: Option<RangeInclusive<Pitch>>,
+    /// on each string, pitches outside the span from the first of these notes at or above the
+    /// open string up to the next second one are left empty
+    pub between: Option<(Note, Note)>,
+    /// plain text without ANSI colors, the root told apart by [RenderOptions::root_marker]
+    pub no_color: bool,
+    pub root_marker: String,
+    /// colors overriding every other coloring of these notes wherever they are shown
+    pub note_colors: HashMap<Note, Color>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            all_note_names: false,
+            labels: Labels::Names,
+            frets_start: 0,
+            orientation: Orientation::Landscape,
+            separator: "\t".to_string(),
+            fixed_width: false,
+            highlight: HashSet::new(),
+            skip_strings: HashSet::new(),
+            only_positions: None,
+            chord: None,
+            tensions: Vec::new(),
+            triad_overlay: None,
+            no_open: false,
+            fret_numbers: false,
+            fret_number_offset: 0,
+            fancy: false,
+            only_root_octaves: false,
+            wrap: None,
+            range: None,
+            between: None,
+            no_color: false,
+            root_marker: "()".to_string(),
+            note_colors: HashMap::new(),
+        }
+    }
+}
+
+/// a fret as printed, shifted by `--fret-number-offset` like the fret number header
+fn fret_label(fret: usize, offset: i32) -> String {
+    (fret as i32 + offset).to_string()
+}
+
+/// characters per tab column, the widest fret number and a dash
+fn tab_column_width(positions: &[(usize, usize)], offset: i32) -> usize {
+    positions
+        .iter()
+        .map(|(_, fret)| fret_label(*fret, offset).len() + 1)
+        .max()
+        .unwrap_or(2)
+}
+
+fn tab_label_width(guitar: &Guitar) -> usize {
+    guitar
+        .strings
+        .iter()
+        .map(|string| string.start.to_string().len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// single note tab of `positions` (zero-based string, fret), highest string on top, the fret
+/// numbers shifted by `offset`
+pub fn render_tab(guitar: &Guitar, positions: &[(usize, usize)], offset: i32) -> String {
+    tab_with_width(
+        guitar,
+        positions,
+        offset,
+        tab_column_width(positions, offset),
+    )
+}
+
+fn tab_with_width(
+    guitar: &Guitar,
+    positions: &[(usize, usize)],
+    offset: i32,
+    width: usize,
+) -> String {
+    let names = guitar
+        .strings
+        .iter()
+        .map(|string| string.start.to_string())
+        .collect::<Vec<_>>();
+    let label_width = tab_label_width(guitar);
+    names
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(index, name)| {
+            let columns = positions
+                .iter()
+                .map(|(string, fret)| match *string == index {
+                    true => format!("{:-<width$}", fret_label(*fret, offset)),
+                    false => "-".repeat(width),
+                })
+                .collect::<String>();
+            format!("{name:<label_width$}|-{columns}|\n")
+        })
+        .collect()
+}
+
+/// [render_tab] with the name of every note, spelled as in `scale`, under its column; the
+/// columns widen to fit the names
+pub fn render_named_tab(
+    guitar: &Guitar,
+    positions: &[(usize, usize)],
+    offset: i32,
+    scale: &Scale,
+) -> String {
+    let names = positions
+        .iter()
+        .map(|(string, fret)| spell_note(scale, guitar.strings[*string].note_at(*fret)))
+        .collect::<Vec<_>>();
+    let width = names
+        .iter()
+        .map(|name| name.len() + 1)
+        .max()
+        .unwrap_or(0)
+        .max(tab_column_width(positions, offset));
+    let names = names
+        .iter()
+        .map(|name| format!("{name:<width$}"))
+        .collect::<String>();
+    let line = format!("{}  {names}", " ".repeat(tab_label_width(guitar)));
+    format!(
+        "{}{}\n",
+        tab_with_width(guitar, positions, offset, width),
+        line.trim_end()
+    )
+}
+
+/// rows are strings (highest string number first), optionally preceded by a fret number
+/// header; the first column holds the labels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Grid {
+    /// splits the cell columns into blocks of `width`, every block keeping the label column
+    pub fn chunks(&self, width: usize) -> Vec<Self> {
+        let columns = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        (1..columns.max(2))
+            .step_by(width.max(1))
+            .map(|start| {
+                let rows = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        let end = (start + width.max(1)).min(row.len());
+                        row.iter()
+                            .take(1)
+                            .chain(row.get(start..end).unwrap_or_default())
+                            .cloned()
+                            .collect()
+                    })
+                    .collect();
+                Self { rows }
+            })
+            .collect()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let rows = (0..width)
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| row.get(column).cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        Self { rows }
+    }
+}
+
+/// character count with ANSI escape sequences left out
+pub fn visible_len(cell: &str) -> usize {
+    let mut len = 0;
+    let mut in_escape = false;
+    for c in cell.chars() {
+        match (in_escape, c) {
+            (false, '\x1b') => in_escape = true,
+            (false, _) => len += 1,
+            (true, 'm') => in_escape = false,
+            (true, _) => {}
+        }
+    }
+    len
+}
+
+/// `text` between the two characters of a marker like `()`, or between two copies of any
+/// other marker such as `*`
+pub fn marked(text: &str, marker: &str) -> String {
+    let mut chars = marker.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(open), Some(close), None) => format!("{open}{text}{close}"),
+        _ => format!("{marker}{text}{marker}"),
+    }
+}
+
+pub fn colored(text: &str, color: &str) -> String {
+    format!("\x1b[{color}m{text}\x1b[0m")
+}
+
+pub fn fret_range(guitar: &Guitar, options: &RenderOptions) -> Range<usize> {
+    let start = match options.no_open {
+        true => options.frets_start.max(1),
+        false => options.frets_start,
+    };
+    start..guitar.notes_per_string
+}
+
+pub fn fretboard_grid(guitar: &Guitar, scale: &Scale, options: &RenderOptions) -> Grid {
+    let layout = Layout::new(guitar, scale, options);
+    let header = std::iter::once(String::new())
+        .chain(
+            layout
+                .frets
+                .clone()
+                .map(|fret| fret_label(fret, options.fret_number_offset)),
+        )
+        .collect::<Vec<_>>();
+    let strings = layout.strings.iter().rev().map(|string| {
+        let cells =
+            string
+                .cells
+                .iter()
+                .map(|cell| match (&cell.label, cell.color, options.no_color) {
+                    (None, _, _) => EMPTY_CELL.to_string(),
+                    (Some(label), _, true) if cell.is_root => marked(label, &options.root_marker),
+                    (Some(label), Some(color), false) => colored(label, color),
+                    (Some(label), _, _) => label.clone(),
+                });
+        std::iter::once(string.label.clone())
+            .chain(cells)
+            .collect::<Vec<_>>()
+    });
+    let rows = match options.fret_numbers {
+        true => std::iter::once(header).chain(strings).collect(),
+        false => strings.collect(),
+    };
+    Grid { rows }
+}
+
+pub fn render_grid(grid: &Grid, options: &RenderOptions) -> String {
+    let rows = match options.orientation {
+        Orientation::Landscape => grid
+            .rows
+            .iter()
+            .map(|row| {
+                let mut row = row.clone();
+                row.insert(row.len().min(1), String::new());
+                row
+            })
+            .collect(),
+        Orientation::Portrait => grid.transpose().rows,
+    };
+    let widths = (0..rows.iter().map(Vec::len).max().unwrap_or(0))
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| visible_len(cell))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    rows.iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| match options.fixed_width {
+                    true => format!("{cell}{}", " ".repeat(width - visible_len(cell))),
+                    false => cell.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join(&options.separator);
+            format!("{line}\n")
+        })
+        .collect()
+}
+
+fn centered(content: &str, width: usize, fill: char) -> String {
+    let padding = width.saturating_sub(visible_len(content));
+    let left = fill.to_string().repeat(padding / 2);
+    let right = fill.to_string().repeat(padding - padding / 2);
+    format!("{left}{content}{right}")
+}
+
+/// strings are drawn as horizontal lines running through the note cells
+pub fn render_fancy(grid: &Grid, title: &str, options: &RenderOptions) -> String {
+    let label_width = grid
+        .rows
+        .iter()
+        .filter_map(|row| row.first())
+        .map(|label| visible_len(label))
+        .max()
+        .unwrap_or(0);
+    let cell_width = grid
+        .rows
+        .iter()
+        .flat_map(|row| row.iter().skip(1))
+        .map(|cell| visible_len(cell))
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let lines = grid
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let is_header = options.fret_numbers && index == 0;
+            let mut cells = row.iter();
+            let label = cells.next().cloned().unwrap_or_default();
+            let cells: String = cells
+                .map(|cell| match (is_header, cell.as_str()) {
+                    (true, _) => centered(cell, cell_width, ' '),
+                    (false, EMPTY_CELL) => centered("", cell_width, '─'),
+                    (false, _) => centered(cell, cell_width, '─'),
+                })
+                .collect();
+            format!(
+                "│ {label}{} {cells} │",
+                " ".repeat(label_width - visible_len(&label))
+            )
+        })
+        .collect::<Vec<_>>();
+    let inner = lines.first().map(|line| visible_len(line) - 2).unwrap_or(0);
+    let title = format!("─ {title} ");
+    let top = format!(
+        "┌{title}{}┐",
+        "─".repeat(inner.saturating_sub(visible_len(&title)))
+    );
+    let bottom = format!("└{}┘", "─".repeat(inner));
+    std::iter::once(top)
+        .chain(lines)
+        .chain(std::iter::once(bottom))
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+/// `6(E) 8 notes, 5(B) 1 note, ...`: how many notes the neck marks on every string
+pub fn per_string_line(guitar: &Guitar, scale: &Scale, options: &RenderOptions) -> String {
+    Layout::new(guitar, scale, options)
+        .labeled_per_string()
+        .iter()
+        .map(|(label, count)| format!("{label} {}", plural(*count, "note")))
+        .join(", ")
+}
+
+/// a named mode's row: its name, the notes with the [Scale::characteristic_degrees] colored,
+/// and the intervals of those degrees
+fn mode_row(mode: &Scale, no_color: bool) -> (String, String, String) {
+    let characteristic = mode.characteristic_degrees();
+    let annotated = mode.annotated_notes();
+    let notes = annotated
+        .iter()
+        .map(|(note, degree, _)| {
+            let name = note.to_string();
+            let padding = " ".repeat(3usize.saturating_sub(name.len()));
+            match characteristic.contains(degree) && !no_color {
+                true => colored(&name, HIGHLIGHT_COLOR) + &padding,
+                false => name + &padding,
+            }
+        })
+        .collect::<String>();
+    let intervals = annotated
+        .iter()
+        .filter(|(_, degree, _)| characteristic.contains(degree))
+        .map(|(_, _, interval)| interval)
+        .join(" ");
+    (mode.to_string(), notes, intervals)
+}
+
+/// the row of a rotation of `parent` no known mode matches: the root with an `(unnamed)`
+/// placeholder, the plain notes and its interval pattern at the end
+fn unnamed_rotation(parent: &Scale, degree: usize) -> (String, String, String) {
+    let mut notes = parent.degrees();
+    notes.rotate_left(degree - 1);
+    let mut intervals = parent.mode.intervals_raw();
+    intervals.rotate_left(degree - 1);
+    let notes = notes
+        .iter()
+        .map(|note| format!("{:<3}", note.to_string()))
+        .collect::<String>();
+    (
+        format!("{} (unnamed)", parent.degrees()[degree - 1]),
+        notes,
+        format!("intervals {}", intervals.iter().join(" ")),
+    )
+}
+
+/// a row per mode of `parent`: its degree, name and notes, the [Scale::characteristic_degrees]
+/// colored unless `no_color` and named by their interval at the end; rotations that are no
+/// known mode keep their row with their interval pattern instead
+pub fn render_mode_grid(parent: &Scale, no_color: bool) -> String {
+    let rows = (1..=parent.degrees().len())
+        .map(|degree| {
+            let row = match parent.mode_of(degree) {
+                Some(mode) => mode_row(&mode, no_color),
+                None => unnamed_rotation(parent, degree),
+            };
+            (degree, row)
+        })
+        .collect::<Vec<_>>();
+    let name_width = rows
+        .iter()
+        .map(|(_, (name, _, _))| name.len())
+        .max()
+        .unwrap_or(0);
+    rows.iter()
+        .map(|(degree, (name, notes, end))| {
+            let line = format!("{degree} {name:<name_width$}  {notes}{end}");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+/// the guitar, the scale and the options a neck is drawn from, for bug reports
+pub fn debug_report(guitar: &Guitar, scale: &Scale, options: &RenderOptions) -> String {
+    let open = guitar
+        .open_pitches()
+        .iter()
+        .map(|pitch| pitch.to_string())
+        .join(" ");
+    format!(
+        "GUITAR: {} strings, {} frets, open {open}\nSTRINGS: {:?}\nSCALE: {scale} ({})\nOPTIONS: {options:#?}\n",
+        guitar.strings.len(),
+        guitar.notes_per_string,
+        guitar.strings,
+        scale.degrees().iter().join(", ")
+    )
+}
+
+pub fn render_fretboard(guitar: &Guitar, scale: &Scale, options: &RenderOptions) -> String {
+    let render = |grid: &Grid, options: &RenderOptions| match options.fancy {
+        true => render_fancy(grid, &scale.to_string(), options),
+        false => render_grid(grid, options),
+    };
+    match options.wrap {
+        Some(width) => {
+            let options = RenderOptions {
+                fret_numbers: true,
+                ..options.clone()
+            };
+            fretboard_grid(guitar, scale, &options)
+                .chunks(width)
+                .iter()
+                .map(|block| render(block, &options))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        None => render(&fretboard_grid(guitar, scale, options), options),
+    }
+}
+
+/// how far along the string, as a fraction of its length from the nut, the note
+/// `semitones` above the open string is stopped in equal temperament
+pub fn fretless_position(semitones: usize) -> f64 {
+    1.0 - 2f64.powf(-(semitones as f64) / Note::COUNT as f64)
+}
+
+/// columns in a fretless ruler, nut included
+pub const FRETLESS_WIDTH: usize = 73;
+
+/// each string as a continuous ruler from the nut (`|`) to the last shown semitone, with
+/// `R` at the roots and `o` at the other marked notes placed by [fretless_position], so the
+/// marks bunch together going up the neck as on a real string
+pub fn render_fretless(guitar: &Guitar, scale: &Scale, options: &RenderOptions) -> String {
+    let layout = Layout::new(guitar, scale, options);
+    let semitones_of = |index: usize, fret| guitar.strings[index].semitones_at(fret);
+    let longest = layout
+        .strings
+        .iter()
+        .filter_map(|string| Some(semitones_of(string.index, string.cells.last()?.fret)))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let scale_end = fretless_position(longest);
+    let label_width = layout
+        .strings
+        .iter()
+        .map(|string| string.label.len())
+        .max()
+        .unwrap_or(0);
+    layout
+        .strings
+        .iter()
+        .rev()
+        .map(|string| {
+            let mut ruler = vec!['-'; FRETLESS_WIDTH];
+            ruler[0] = '|';
+            for cell in string.cells.iter().filter(|cell| cell.label.is_some()) {
+                let position = fretless_position(semitones_of(string.index, cell.fret));
+                let column = (position / scale_end * (FRETLESS_WIDTH - 1) as f64).round();
+                ruler[column as usize] = match cell.is_root {
+                    true => 'R',
+                    false => 'o',
+                };
+            }
+            let ruler = ruler.into_iter().collect::<String>();
+            format!("{:<label_width$} {ruler}\n", string.label)
+        })
+        .collect()
+}
+
+/// frets summed into each heatmap column, about what a hand covers without shifting
+pub const HEATMAP_WINDOW: usize = 4;
+/// heatmap intensities, from no scale notes up to the densest window shown
+const HEATMAP_SHADES: [char; 5] = [' ', '.', ':', '*', '#'];
+
+/// (fret, count) of the marked notes on every shown string within [HEATMAP_WINDOW] frets
+/// starting at each shown fret
+pub fn note_density(
+    guitar: &Guitar,
+    scale: &Scale,
+    options: &RenderOptions,
+) -> Vec<(usize, usize)> {
+    let layout = Layout::new(guitar, scale, options);
+    let marked = layout
+        .strings
+        .iter()
+        .flat_map(|string| &string.cells)
+        .filter(|cell| cell.label.is_some())
+        .map(|cell| cell.fret)
+        .collect::<Vec<_>>();
+    layout
+        .frets
+        .clone()
+        .map(|start| {
+            let window = start..start + HEATMAP_WINDOW;
+            (
+                start,
+                marked.iter().filter(|fret| window.contains(fret)).count(),
+            )
+        })
+        .collect()
+}
+
+/// fret numbers over one shade per fret, darker where [note_density] finds more notes
+pub fn render_heatmap(guitar: &Guitar, scale: &Scale, options: &RenderOptions) -> String {
+    let density = note_density(guitar, scale, options);
+    let densest = density
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let (frets, shades): (String, String) = density
+        .iter()
+        .map(|(fret, count)| {
+            let shade = HEATMAP_SHADES[count * (HEATMAP_SHADES.len() - 1) / densest];
+            (format!("{fret:>3}"), format!("{shade:>3}"))
+        })
+        .unzip();
+    format!("{frets}\n{shades}\n")
+}
+
+/// fret rows drawn below the nut in a chord diagram, at the least
+const DIAGRAM_FRETS: usize = 4;
+
+/// a chord box for `voicing` (a fret or a mute per string, lowest first): the name on top,
+/// `x`/`o` for muted and open strings, then the nut and one row per fret with `O` where a
+/// finger goes; voicings out of reach of the nut start at their lowest fret, noted as `5fr`
+pub fn chord_diagram(name: &str, voicing: &[Option<usize>]) -> Vec<String> {
+    let fretted = voicing.iter().flatten().copied().filter(|fret| *fret > 0);
+    let highest = fretted.clone().max().unwrap_or(0);
+    let base = match highest <= DIAGRAM_FRETS {
+        true => 1,
+        false => fretted.min().unwrap_or(1),
+    };
+    let rows = DIAGRAM_FRETS.max(highest + 1 - base);
+    let line =
+        |cell: &dyn Fn(Option<usize>) -> char| voicing.iter().map(|fret| cell(*fret)).join(" ");
+    let mut lines = vec![
+        name.to_string(),
+        line(&|fret| match fret {
+            None => 'x',
+            Some(0) => 'o',
+            Some(_) => ' ',
+        }),
+        match base {
+            1 => "=",
+            _ => "-",
+        }
+        .repeat((voicing.len() * 2).saturating_sub(1)),
+    ];
+    for row in 0..rows {
+        let fret = base + row;
+        let cells = line(&|played| match played == Some(fret) {
+            true => 'O',
+            false => '|',
+        });
+        lines.push(match (row, base) {
+            (0, 1) | (1.., _) => cells,
+            (0, base) => format!("{cells} {base}fr"),
+        });
+    }
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    lines
+        .into_iter()
+        .map(|line| format!("{line:<width$}"))
+        .collect()
+}
+
+/// a progression chord [Guitar::find_voicing] has no voicing for
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(fmt = "{} has no fingering within {} frets", chord, max_stretch)]
+pub struct UnplayableChordError {
+    #[error(not(source))]
+    pub chord: Chord,
+    pub max_stretch: usize,
+}
+
+/// a chord diagram per chord side by side, each with the voicing [Guitar::find_voicing]
+/// picks within `max_stretch` frets
+pub fn render_progression(
+    guitar: &Guitar,
+    chords: &[Chord],
+    max_stretch: usize,
+) -> Result<String, UnplayableChordError> {
+    let diagrams = chords
+        .iter()
+        .map(|chord| {
+            let voicing = guitar
+                .find_voicing(&chord.voiced_notes(), max_stretch)
+                .ok_or(UnplayableChordError {
+                    chord: *chord,
+                    max_stretch,
+                })?;
+            Ok(chord_diagram(&chord.to_string(), &voicing))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let height = diagrams.iter().map(Vec::len).max().unwrap_or(0);
+    Ok((0..height)
+        .map(|row| {
+            let line = diagrams
+                .iter()
+                .map(|diagram| {
+                    let width = diagram[0].chars().count();
+                    diagram
+                        .get(row)
+                        .cloned()
+                        .unwrap_or_else(|| " ".repeat(width))
+                })
+                .join("   ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect())
+}
+
+/// renders one scale per non-empty input line, reporting unparsable lines in place
+pub fn render_batch(input: &str, guitar: &Guitar, options: &RenderOptions) -> String {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(num, line)| match line.parse::<Scale>() {
+            Ok(scale) => format!(
+                "SCALE: {scale}\n{}\n",
+                render_fretboard(guitar, &scale, options)
+            ),
+            Err(error) => format!("ERROR: line {}: {error}\n\n", num + 1),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guitar::Tuning;
+    use crate::scale::ScaleMode;
+
+    fn cells(rendered: &str) -> Vec<Vec<String>> {
+        rendered
+            .lines()
+            .map(|line| {
+                line.split('\t')
+                    .filter(|cell| !cell.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_portrait_is_transpose() {
+        let guitar = Guitar::from_tuning(3, Note::E, 5, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::G,
+            mode: ScaleMode::Major,
+        };
+        let options = |orientation| RenderOptions {
+            frets_start: 1,
+            orientation,
+            ..Default::default()
+        };
+        let landscape = render_fretboard(&guitar, &scale, &options(Orientation::Landscape));
+        let portrait = render_fretboard(&guitar, &scale, &options(Orientation::Portrait));
+        let landscape = Grid {
+            rows: cells(&landscape),
+        };
+        assert_eq!(landscape.rows.len(), 3);
+        assert_eq!(landscape.rows[0].len(), 5);
+        assert_eq!(landscape.transpose().rows, cells(&portrait));
+        assert_eq!(landscape.transpose().transpose(), landscape);
+    }
+
+    #[test]
+    fn test_fixed_width_rows_align() {
+        let guitar = Guitar::from_tuning(6, Note::E, 12, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::A,
+            mode: ScaleMode::Major,
+        };
+        for orientation in [Orientation::Landscape, Orientation::Portrait] {
+            let options = RenderOptions {
+                all_note_names: true,
+                orientation,
+                separator: " ".to_string(),
+                fixed_width: true,
+                ..Default::default()
+            };
+            let rendered = render_fretboard(&guitar, &scale, &options);
+            let lengths = rendered.lines().map(visible_len).collect::<Vec<_>>();
+            assert!(lengths.len() > 1);
+            assert!(lengths.iter().all(|len| *len == lengths[0]), "{rendered}");
+            assert!(!rendered.contains('\t'));
+        }
+    }
+
+    #[test]
+    fn test_visible_len_skips_ansi() {
+        assert_eq!(visible_len("\x1b[93mC#\x1b[0m"), 2);
+        assert_eq!(visible_len("|"), 1);
+    }
+
+    #[test]
+    fn test_highlight_notes_columns() {
+        let guitar = Guitar::from_tuning(1, Note::E, 12, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::A,
+            mode: ScaleMode::Minor,
+        };
+        let options = RenderOptions {
+            highlight: [Note::Fs, Note::C].into_iter().collect(),
+            ..Default::default()
+        };
+        let grid = fretboard_grid(&guitar, &scale, &options);
+        let highlighted = grid.rows[0]
+            .iter()
+            .skip(1)
+            .enumerate()
+            .filter(|(_, cell)| cell.contains(HIGHLIGHT_COLOR))
+            .map(|(fret, _)| fret)
+            .collect::<Vec<_>>();
+        assert_eq!(highlighted, vec![2, 8]);
+        assert_eq!(grid.rows[0][6], colored("A", ROOT_COLOR));
+        assert_eq!(grid.rows[0][5], "|");
+    }
+
+    #[test]
+    fn test_slash_chord_bass_marked() {
+        let guitar = Guitar::from_tuning(1, Note::E, 12, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::D,
+            mode: ScaleMode::Dorian,
+        };
+        let options = RenderOptions {
+            chord: Some("Am/C".parse().unwrap()),
+            ..Default::default()
+        };
+        let row = &fretboard_grid(&guitar, &scale, &options).rows[0];
+        assert_eq!(row[1], colored("E", CHORD_COLOR));
+        assert_eq!(row[6], colored("A", CHORD_COLOR));
+        assert_eq!(row[9], colored("C", BASS_COLOR));
+        assert_eq!(row[11], colored("D", ROOT_COLOR));
+    }
+
+    #[test]
+    fn test_no_open_skips_fret_zero() {
+        let guitar = Guitar::from_tuning(1, Note::E, 5, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = |no_open, frets_start| RenderOptions {
+            all_note_names: true,
+            no_open,
+            frets_start,
+            fret_numbers: true,
+            ..Default::default()
+        };
+        let grid = fretboard_grid(&guitar, &scale, &options(true, 0));
+        assert_eq!(grid.rows[0], vec!["", "1", "2", "3", "4"]);
+        assert_eq!(grid.rows[1][1..], ["F", "|", "G", "|"]);
+        let with_open = fretboard_grid(&guitar, &scale, &options(false, 0));
+        assert_eq!(with_open.rows[0][1], "0");
+        assert_eq!(with_open.rows[1][1], "E");
+        let later = fretboard_grid(&guitar, &scale, &options(true, 3));
+        assert_eq!(later.rows[0][1], "3");
+    }
+
+    #[test]
+    fn test_fancy_borders() {
+        let guitar = Guitar::from_tuning(2, Note::E, 3, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = RenderOptions {
+            all_note_names: true,
+            fancy: true,
+            ..Default::default()
+        };
+        let rendered = render_fretboard(&guitar, &scale, &options);
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "┌─ C Major ──────┐");
+        assert_eq!(lines[1], "│ 2(A) ─A─────B─ │");
+        assert_eq!(lines[2], "│ 1(E) ─E──F──── │");
+        assert_eq!(lines[3], "└────────────────┘");
+        assert!(lines
+            .iter()
+            .all(|line| visible_len(line) == visible_len(lines[0])));
+    }
+
+    #[test]
+    fn test_fret_number_offset() {
+        let guitar = Guitar::from_tuning(1, Note::E, 4, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let grid = |fret_number_offset| {
+            fretboard_grid(
+                &guitar,
+                &scale,
+                &RenderOptions {
+                    fret_numbers: true,
+                    fret_number_offset,
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(grid(0).rows[0], vec!["", "0", "1", "2", "3"]);
+        assert_eq!(grid(1).rows[0], vec!["", "1", "2", "3", "4"]);
+        assert_eq!(grid(0).rows[1], grid(1).rows[1]);
+    }
+
+    #[test]
+    fn test_triad_overlay() {
+        let guitar = Guitar::from_tuning(1, Note::E, 12, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let row = |triad_overlay| {
+            let options = RenderOptions {
+                triad_overlay: Some(triad_overlay),
+                ..Default::default()
+            };
+            fretboard_grid(&guitar, &scale, &options).rows[0].clone()
+        };
+        let tonic = row(TriadOverlay::Degrees(vec![1]));
+        let marked = tonic
+            .iter()
+            .skip(1)
+            .enumerate()
+            .filter(|(_, cell)| cell.contains('\x1b'))
+            .map(|(fret, _)| guitar.strings[0].note_at(fret))
+            .collect::<Vec<_>>();
+        assert_eq!(marked, vec![Note::E, Note::G, Note::C]);
+        assert_eq!(tonic[1], colored("E", DEGREE_COLORS[0]));
+        let all = row(TriadOverlay::All);
+        assert_eq!(all[2], colored("F", DEGREE_COLORS[1]));
+        assert_eq!(all[4], colored("G", DEGREE_COLORS[0]));
+    }
+
+    #[test]
+    fn test_triad_overlay_shared_notes() {
+        let guitar = Guitar::from_tuning(1, Note::E, 12, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let row = |degrees: Vec<usize>| {
+            let options = RenderOptions {
+                triad_overlay: Some(TriadOverlay::Degrees(degrees)),
+                ..Default::default()
+            };
+            fretboard_grid(&guitar, &scale, &options).rows[0].clone()
+        };
+        // IV is F A C and ii is D F A, F and A take the color of the first one listed
+        let subdominant_first = row(vec![4, 2]);
+        assert_eq!(subdominant_first[2], colored("F", DEGREE_COLORS[3]));
+        assert_eq!(subdominant_first[6], colored("A", DEGREE_COLORS[3]));
+        assert_eq!(subdominant_first[11], colored("D", DEGREE_COLORS[1]));
+        let supertonic_first = row(vec![2, 4]);
+        assert_eq!(supertonic_first[2], colored("F", DEGREE_COLORS[1]));
+        assert_eq!(supertonic_first[6], colored("A", DEGREE_COLORS[1]));
+        assert_eq!(supertonic_first[9], colored("C", ROOT_COLOR));
+    }
+
+    #[test]
+    fn test_triad_overlay_passing_note() {
+        let guitar = Guitar::from_tuning(1, Note::E, 12, Tuning::Fourths);
+        let scale = "G bebop-dominant".parse::<Scale>().unwrap();
+        let options = RenderOptions {
+            triad_overlay: Some(TriadOverlay::All),
+            ..Default::default()
+        };
+        let row = &fretboard_grid(&guitar, &scale, &options).rows[0];
+        assert_eq!(row[3], colored("F#", PASSING_COLOR));
+        assert_eq!(row[2], colored("F", DEGREE_COLORS[2]));
+    }
+
+    #[test]
+    fn test_render_batch() {
+        let guitar = Guitar::from_tuning(2, Note::E, 12, Tuning::Fourths);
+        let options = RenderOptions::default();
+        let rendered = render_batch("C major\nnonsense\n\nA dorian\n", &guitar, &options);
+        assert!(rendered.contains("SCALE: C Major\n"));
+        assert!(rendered.contains("SCALE: A Dorian\n"));
+        assert!(rendered.contains("ERROR: line 2: invalid scale `nonsense`"));
+        let c_major = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        assert!(rendered.contains(&render_fretboard(&guitar, &c_major, &options)));
+    }
+
+    #[test]
+    fn test_only_root_octaves() {
+        let guitar = Guitar::from_tuning(6, Note::E, 25, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::E,
+            mode: ScaleMode::Minor,
+        };
+        let options = RenderOptions {
+            only_root_octaves: true,
+            ..Default::default()
+        };
+        let grid = fretboard_grid(&guitar, &scale, &options);
+        let low_e = grid.rows.last().unwrap();
+        assert_eq!(low_e[1], colored("R", ROOT_COLOR));
+        assert_eq!(low_e[13], colored("R+1", HIGHLIGHT_COLOR));
+        assert_eq!(low_e[25], colored("R+2", HIGHLIGHT_COLOR));
+        assert_eq!(low_e[2], EMPTY_CELL);
+        // D string, 2nd fret is the same E3 as the low string's 12th fret
+        assert_eq!(grid.rows[3][3], colored("R+1", HIGHLIGHT_COLOR));
+    }
+
+    #[test]
+    fn test_zero_length_neck() {
+        let guitar = Guitar::from_tuning(3, Note::E, 0, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        for frets_start in [0, 5] {
+            let options = RenderOptions {
+                frets_start,
+                ..Default::default()
+            };
+            assert!(fret_range(&guitar, &options).is_empty());
+            let rendered = render_fretboard(&guitar, &scale, &options);
+            assert_eq!(rendered, "3(D)\t\n2(A)\t\n1(E)\t\n");
+            let fancy = RenderOptions {
+                fancy: true,
+                ..options
+            };
+            assert_eq!(render_fretboard(&guitar, &scale, &fancy).lines().count(), 5);
+        }
+    }
+
+    #[test]
+    fn test_solfege_labels() {
+        let guitar = Guitar::from_tuning(1, Note::C, 12, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = RenderOptions {
+            labels: Labels::Solfege,
+            ..Default::default()
+        };
+        let row = &fretboard_grid(&guitar, &scale, &options).rows[0];
+        let labels = row
+            .iter()
+            .skip(1)
+            .filter(|cell| cell.as_str() != EMPTY_CELL)
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut expected = vec![colored("Do", ROOT_COLOR)];
+        expected.extend(["Re", "Mi", "Fa", "Sol", "La", "Ti"].map(str::to_string));
+        assert_eq!(labels, expected);
+    }
+
+    #[test]
+    fn test_fixed_do_labels() {
+        let guitar = Guitar::from_tuning(1, Note::Fs, 1, Tuning::Fourths);
+        let options = RenderOptions {
+            labels: Labels::SolfegeFixed,
+            ..Default::default()
+        };
+        for (start_note, mode) in [
+            (Note::G, ScaleMode::Major),
+            (Note::D, ScaleMode::Major),
+            (Note::B, ScaleMode::Minor),
+        ] {
+            let scale = Scale { start_note, mode };
+            let row = &fretboard_grid(&guitar, &scale, &options).rows[0];
+            assert_eq!(row[1], "Fa#");
+        }
+        let scale = Scale {
+            start_note: Note::Fs,
+            mode: ScaleMode::Major,
+        };
+        let row = &fretboard_grid(&guitar, &scale, &options).rows[0];
+        assert_eq!(row[1], colored("Fa#", ROOT_COLOR));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let guitar = Guitar::from_tuning(2, Note::E, 24, Tuning::Fourths);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = RenderOptions {
+            wrap: Some(12),
+            ..Default::default()
+        };
+        let rendered = render_fretboard(&guitar, &scale, &options);
+        let blocks = rendered.split("\n\n").collect::<Vec<_>>();
+        assert_eq!(blocks.len(), 2);
+        let numbers = |block: &str| cells(block)[0].clone();
+        let expected = |range: Range<usize>| range.map(|fret| fret.to_string()).collect::<Vec<_>>();
+        assert_eq!(numbers(blocks[0]), expected(0..12));
+        assert_eq!(numbers(blocks[1]), expected(12..24));
+        assert!(blocks.iter().all(|block| block.lines().count() == 3));
+        assert_eq!(cells(blocks[1])[2][0], "1(E)");
+    }
+
+    #[test]
+    fn test_skip_strings() {
+        let guitar = Guitar::from_tuning(6, Note::E, 5, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = RenderOptions {
+            skip_strings: [1, 2].into_iter().collect(),
+            ..Default::default()
+        };
+        let rendered = cells(&render_fretboard(&guitar, &scale, &options));
+        let labels = rendered
+            .iter()
+            .map(|row| row[0].as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!["6(E)", "5(B)", "4(G)", "3(D)"]);
+    }
+
+    #[test]
+    fn test_note_colors() {
+        let note_colors = parse_note_colors("C=red, g=Green", NoteInput::English).unwrap();
+        assert_eq!(note_colors[&Note::C], Color::Red);
+        assert_eq!(note_colors[&Note::G], Color::Green);
+        assert!(parse_note_colors("C=purple", NoteInput::English).is_err());
+        assert!(parse_note_colors("C", NoteInput::English).is_err());
+        let italian = parse_note_colors("Sol=red", NoteInput::Italian).unwrap();
+        assert_eq!(italian.get(&Note::G), Some(&Color::Red));
+        let guitar = Guitar::from_tuning(1, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = RenderOptions {
+            all_note_names: true,
+            note_colors,
+            ..RenderOptions::default()
+        };
+        let grid = fretboard_grid(&guitar, &scale, &options);
+        let row = &grid.rows[0];
+        assert_eq!(row[1], "E");
+        assert_eq!(row[4], colored("G", "92"));
+        assert_eq!(row[9], colored("C", "91"));
+    }
+
+    #[test]
+    fn test_render_tab() {
+        let guitar = Guitar::from_tuning(3, Note::E, 13, Tuning::Standard);
+        let tab = render_tab(&guitar, &[(0, 3), (1, 12), (2, 0)], 0);
+        assert_eq!(tab, "D|-------0--|\nA|----12----|\nE|-3--------|\n");
+        let tab = render_tab(&guitar, &[(0, 3), (1, 12), (2, 0)], 1);
+        assert_eq!(tab, "D|-------1--|\nA|----13----|\nE|-4--------|\n");
+    }
+
+    #[test]
+    fn test_root_marker_without_color() {
+        let guitar = Guitar::from_tuning(1, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let options = |root_marker: &str| RenderOptions {
+            no_color: true,
+            root_marker: root_marker.to_string(),
+            all_note_names: true,
+            ..RenderOptions::default()
+        };
+        let row = fretboard_grid(&guitar, &scale, &options("()")).rows[0].clone();
+        assert_eq!(row[9], "(C)");
+        assert_eq!(row[4], "G");
+        assert!(row.iter().all(|cell| !cell.contains('\x1b')));
+        let row = fretboard_grid(&guitar, &scale, &options("*")).rows[0].clone();
+        assert_eq!(row[9], "*C*");
+        assert_eq!(marked("C", "[]"), "[C]");
+    }
+
+    #[test]
+    fn test_fretless_positions() {
+        assert_eq!(fretless_position(0), 0.0);
+        assert!((fretless_position(12) - 0.5).abs() < 1e-12);
+        assert!((fretless_position(24) - 0.75).abs() < 1e-12);
+        assert!((fretless_position(7) - (1.0 - 2.0 / 3.0)).abs() < 2e-3);
+        for semitones in 0..24 {
+            let ratio =
+                (1.0 - fretless_position(semitones + 1)) / (1.0 - fretless_position(semitones));
+            assert!((ratio - 2f64.powf(-1.0 / 12.0)).abs() < 1e-12);
+        }
+        let guitar = Guitar::from_tuning(1, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::E,
+            mode: ScaleMode::Minor,
+        };
+        let rendered = render_fretless(&guitar, &scale, &RenderOptions::default());
+        let ruler = rendered.trim_end().split_once(' ').unwrap().1;
+        assert_eq!(ruler.len(), FRETLESS_WIDTH);
+        assert_eq!(ruler.find('R'), Some(0));
+        assert_eq!(ruler.rfind('R'), Some(FRETLESS_WIDTH - 1));
+        assert_eq!(ruler.matches('o').count(), 6);
+    }
+
+    #[test]
+    fn test_chord_diagram() {
+        let diagram = chord_diagram("C", &[None, Some(3), Some(2), Some(0), Some(1), Some(0)]);
+        assert_eq!(
+            diagram,
+            [
+                "C          ",
+                "x     o   o",
+                "===========",
+                "| | | | O |",
+                "| | O | | |",
+                "| O | | | |",
+                "| | | | | |",
+            ]
+        );
+        let barre = chord_diagram("Bm", &[None, Some(7), Some(9), Some(9), Some(8), Some(7)]);
+        assert_eq!(barre[2].trim_end(), "-----------");
+        assert_eq!(barre[3], "| O | | | O 7fr");
+    }
+
+    #[test]
+    fn test_render_progression() {
+        let guitar = Guitar::from_tuning(6, Note::E, 15, Tuning::Standard);
+        let chords = ["C", "Am", "F", "G"]
+            .iter()
+            .map(|chord| chord.parse::<Chord>().unwrap())
+            .collect::<Vec<_>>();
+        let rendered = render_progression(&guitar, &chords, 4).unwrap();
+        let names = rendered.lines().next().unwrap();
+        assert_eq!(
+            names.split_whitespace().collect::<Vec<_>>(),
+            ["C", "Am", "F", "G"]
+        );
+        let nut = rendered.lines().nth(2).unwrap();
+        assert_eq!(nut.split_whitespace().count(), 4);
+        let offsets = ["C", "Am", "F", "G"].map(|name| names.find(name).unwrap());
+        assert_eq!(offsets, [0, 14, 28, 42]);
+    }
+
+    #[test]
+    fn test_unplayable_progression() {
+        let guitar = Guitar::from_tuning(2, Note::E, 15, Tuning::Standard);
+        let chords = ["C", "Cmaj7"]
+            .iter()
+            .map(|chord| chord.parse::<Chord>().unwrap())
+            .collect::<Vec<_>>();
+        let error = render_progression(&guitar, &chords, 4).unwrap_err();
+        assert_eq!(error.chord, chords[0]);
+        assert_eq!(error.to_string(), "C has no fingering within 4 frets");
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::A,
+            mode: ScaleMode::Minor,
+        };
+        let options = RenderOptions {
+            only_positions: Some(guitar.pentatonic_box(Note::A, 1).into_iter().collect()),
+            ..RenderOptions::default()
+        };
+        let density = note_density(&guitar, &scale, &options);
+        assert_eq!(density.len(), 13);
+        assert_eq!(density[5], (5, 12));
+        assert!(density[5].1 > density[1].1);
+        assert_eq!(density[9].1, 0);
+        let heatmap = render_heatmap(&guitar, &scale, &options);
+        let (frets, shades) = heatmap.trim_end_matches('\n').split_once('\n').unwrap();
+        assert_eq!(frets.len(), shades.len());
+        assert_eq!(shades.chars().nth(5 * 3 + 2), Some('#'));
+        assert_eq!(shades.chars().nth(9 * 3 + 2), Some(' '));
+    }
+
+    #[test]
+    fn test_named_tab_spelling() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let d_major: Scale = "D major".parse().unwrap();
+        let melody = d_major.degrees();
+        let positions = guitar.transcribe(&melody, 12).unwrap();
+        let tab = render_named_tab(&guitar, &positions, 0, &d_major);
+        let names = tab.lines().last().unwrap();
+        assert_eq!(
+            names.split_whitespace().collect::<Vec<_>>(),
+            ["D", "E", "F#", "G", "A", "B", "C#"]
+        );
+        assert!(!names.contains('b'));
+        let f_major: Scale = "F major".parse().unwrap();
+        let positions = guitar.transcribe(&[Note::As, Note::Cs], 12).unwrap();
+        let tab = render_named_tab(&guitar, &positions, 0, &f_major);
+        assert_eq!(tab.lines().last().unwrap().trim(), "Bb Db");
+    }
+
+    #[test]
+    fn test_debug_report() {
+        let guitar = Guitar::from_tuning(6, Note::E, 13, Tuning::Standard);
+        let scale = Scale {
+            start_note: Note::G,
+            mode: ScaleMode::Major,
+        };
+        let report = debug_report(&guitar, &scale, &RenderOptions::default());
+        assert!(report.starts_with("GUITAR: 6 strings, 13 frets, open E2 A2 D3 G3 B3 E4\n"));
+        for string in &guitar.strings {
+            assert!(report.contains(&format!("start: {:?}", string.start)));
+        }
+        assert!(report.contains("SCALE: G Major (G, A, B, C, D, E, F#)"));
+        assert!(report.contains("frets_start: 0"));
+    }
+
+    #[test]
+    fn test_mode_grid() {
+        let c_major: Scale = "C major".parse().unwrap();
+        let grid = render_mode_grid(&c_major, false);
+        let rows = grid.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 7);
+        let roots = rows
+            .iter()
+            .map(|row| row.split_whitespace().nth(1).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(roots, ["C", "D", "E", "F", "G", "A", "B"]);
+        assert_eq!(rows[0], "1 C Major       C  D  E  F  G  A  B");
+        assert!(rows[3].starts_with("4 F Lydian      F  G  A  "));
+        assert!(rows[3].contains(&format!("{}  C", colored("B", HIGHLIGHT_COLOR))));
+        assert!(rows[3].ends_with("A4"));
+        assert!(rows[6].ends_with("m2 d5"));
+        let melodic: Scale = "A melodic-minor".parse().unwrap();
+        let grid = render_mode_grid(&melodic, false);
+        let rows = grid.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 7);
+        assert!(rows[0].starts_with("1 A MelodicMinor  A  B  C  D  E  "));
+        assert_eq!(
+            rows[1],
+            "2 B (unnamed)     B  C  D  E  F# G# A  intervals 1 2 2 2 2 1 2"
+        );
+    }
+
+    #[test]
+    fn test_mode_grid_without_color() {
+        let c_major: Scale = "C major".parse().unwrap();
+        let plain = render_mode_grid(&c_major, true);
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(
+            plain.lines().nth(3),
+            Some("4 F Lydian      F  G  A  B  C  D  E  A4")
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::chord::Chord;
+use crate::guitar::{Guitar, Tuning};
+use crate::note::Note;
+use crate::render::{render_fretboard, RenderOptions};
+use crate::scale::Scale;
+
+/// everything the REPL keeps between commands
+#[derive(Debug, Clone)]
+pub struct ReplState {
+    pub scale: Scale,
+    pub tuning: Tuning,
+    pub string_count: usize,
+    pub frets_end: usize,
+    pub capo: i32,
+    pub options: RenderOptions,
+}
+
+pub const REPL_HELP: &str = "commands: scale <root> <mode>, chord <symbol>, chord none, \
+transpose <+-semitones>, tuning <preset or notes>, quit";
+
+impl ReplState {
+    pub fn guitar(&self) -> Guitar {
+        Guitar::from_tuning_for_scale(
+            self.string_count,
+            Note::E,
+            self.frets_end,
+            self.tuning.clone(),
+            Some(&self.scale),
+        )
+        .with_capo(self.capo)
+    }
+
+    /// the current scale and neck, as printed after every command
+    pub fn render(&self) -> String {
+        let chord = match &self.options.chord {
+            Some(chord) => format!("CHORD: {chord}\n"),
+            None => String::new(),
+        };
+        format!(
+            "SCALE: {}\nTUNING: {}\n{chord}\n{}",
+            self.scale,
+            self.tuning,
+            render_fretboard(&self.guitar(), &self.scale, &self.options)
+        )
+    }
+
+    /// applies one command line and returns what to print, `None` once the user quits
+    pub fn execute(&mut self, line: &str) -> Option<String> {
+        let (command, argument) = line
+            .trim()
+            .split_once(char::is_whitespace)
+            .map(|(command, argument)| (command, argument.trim()))
+            .unwrap_or((line.trim(), ""));
+        let result = match command {
+            "quit" | "exit" => return None,
+            "" => return Some(String::new()),
+            "help" => return Some(format!("{REPL_HELP}\n")),
+            "scale" => argument
+                .parse::<Scale>()
+                .map(|scale| self.scale = scale)
+                .map_err(|error| error.to_string()),
+            "chord" if argument.eq_ignore_ascii_case("none") => {
+                self.options.chord = None;
+                Ok(())
+            }
+            "chord" => argument
+                .parse::<Chord>()
+                .map(|chord| self.options.chord = Some(chord))
+                .map_err(|error| error.to_string()),
+            "transpose" => argument
+                .parse::<i32>()
+                .map(|semitones| self.transpose(semitones))
+                .map_err(|_| format!("invalid transposition `{argument}`, expected e.g. +2")),
+            "tuning" => argument
+                .parse::<Tuning>()
+                .map(|tuning| self.tuning = tuning)
+                .map_err(|error| error.to_string()),
+            other => Err(format!("unknown command `{other}`, {REPL_HELP}")),
+        };
+        Some(match result {
+            Ok(()) => self.render(),
+            Err(error) => format!("ERROR: {error}\n"),
+        })
+    }
+
+    /// moves the scale and the shown chord by `semitones`
+    pub fn transpose(&mut self, semitones: i32) {
+        self.scale.start_note = self.scale.start_note.offset_by(semitones);
+        if let Some(chord) = &mut self.options.chord {
+            chord.root = chord.root.offset_by(semitones);
+            chord.bass = chord.bass.map(|bass| bass.offset_by(semitones));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scale::ScaleMode;
+
+    fn state() -> ReplState {
+        ReplState {
+            scale: Scale {
+                start_note: Note::C,
+                mode: ScaleMode::Major,
+            },
+            tuning: Tuning::Standard,
+            string_count: 6,
+            frets_end: 5,
+            capo: 0,
+            options: RenderOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_repl_commands() {
+        let mut state = state();
+        let output = state.execute("scale A dorian").unwrap();
+        assert!(output.starts_with("SCALE: A Dorian\nTUNING: Standard\n"));
+        state.execute("chord Dm7").unwrap();
+        let output = state.execute("transpose +2").unwrap();
+        assert!(output.starts_with("SCALE: B Dorian\nTUNING: Standard\nCHORD: Em7\n"));
+        assert_eq!(state.scale.start_note, Note::B);
+        state.execute("tuning D,A,D,G,B,E").unwrap();
+        assert_eq!(state.guitar().strings[0].start, Note::D);
+        assert!(state
+            .execute("scale H major")
+            .unwrap()
+            .starts_with("ERROR: "));
+        assert!(state
+            .execute("strum")
+            .unwrap()
+            .starts_with("ERROR: unknown command"));
+        assert_eq!(state.scale.start_note, Note::B);
+        assert_eq!(state.execute("quit"), None);
+    }
+}
@@ -0,0 +1,1296 @@
+use crate::chord::{roman_numeral, ChordQuality, ChordSize};
+use crate::interval::Interval;
+use crate::note::{note_bitmask, notes_of_bitmask, Note};
+use crate::pitch::Pitch;
+use clap::ValueEnum;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::RngExt;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::str::FromStr;
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum, EnumIter, EnumCount)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum ScaleMode {
+    #[value(alias = "ionian")]
+    Major,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    #[value(alias = "aeolian")]
+    Minor,
+    Locrian,
+    MelodicMinor,
+    HungarianMinor,
+    NeapolitanMinor,
+    PhrygianDominant,
+    HarmonicMajor,
+    /// mixolydian with the major seventh added as a passing note
+    BebopDominant,
+    /// major with the sharp fifth added as a passing note
+    BebopMajor,
+}
+
+impl ScaleMode {
+    pub fn intervals_raw(self) -> Vec<usize> {
+        match self {
+            ScaleMode::Major => {
+                vec![2, 2, 1, 2, 2, 2, 1]
+            }
+            ScaleMode::Dorian => {
+                vec![2, 1, 2, 2, 2, 1, 2]
+            }
+            ScaleMode::Phrygian => {
+                vec![1, 2, 2, 2, 1, 2, 2]
+            }
+            ScaleMode::Lydian => {
+                vec![2, 2, 2, 1, 2, 2, 1]
+            }
+            ScaleMode::Mixolydian => {
+                vec![2, 2, 1, 2, 2, 1, 2]
+            }
+            ScaleMode::Minor => {
+                vec![2, 1, 2, 2, 1, 2, 2]
+            }
+            ScaleMode::Locrian => {
+                vec![1, 2, 2, 1, 2, 2, 2]
+            }
+            ScaleMode::MelodicMinor => {
+                vec![2, 1, 2, 2, 2, 2, 1]
+            }
+            ScaleMode::HungarianMinor => {
+                vec![2, 1, 3, 1, 1, 3, 1]
+            }
+            ScaleMode::NeapolitanMinor => {
+                vec![1, 2, 2, 2, 1, 3, 1]
+            }
+            ScaleMode::PhrygianDominant => {
+                vec![1, 3, 1, 2, 1, 2, 2]
+            }
+            ScaleMode::HarmonicMajor => {
+                vec![2, 2, 1, 2, 1, 3, 1]
+            }
+            ScaleMode::BebopDominant => {
+                vec![2, 2, 1, 2, 2, 1, 1, 1]
+            }
+            ScaleMode::BebopMajor => {
+                vec![2, 2, 1, 2, 1, 1, 2, 1]
+            }
+        }
+    }
+
+    /// intervals walked when the scale is played downwards, listed in ascending order
+    pub fn descending_intervals_raw(self) -> Vec<usize> {
+        match self {
+            ScaleMode::MelodicMinor => ScaleMode::Minor.intervals_raw(),
+            other => other.intervals_raw(),
+        }
+    }
+    pub fn intervals(self) -> impl Iterator<Item = usize> {
+        self.intervals_raw().into_iter().cycle()
+    }
+
+    /// 0-based index of the chromatic note the bebop modes add to a seven note scale
+    pub fn passing_degree(self) -> Option<usize> {
+        match self {
+            ScaleMode::BebopDominant => Some(7),
+            ScaleMode::BebopMajor => Some(5),
+            _ => None,
+        }
+    }
+
+    /// the mode with exactly this ascending interval pattern
+    pub fn from_intervals(intervals: &[usize]) -> Option<Self> {
+        Self::iter().find(|mode| mode.intervals_raw() == intervals)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scale {
+    pub start_note: Note,
+    pub mode: ScaleMode,
+}
+
+/// the widest step allowed between neighbouring scale notes, a major third
+pub const MAX_INTERVAL: usize = 4;
+
+/// why a list of intervals does not make a scale
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum ScaleError {
+    #[display(fmt = "interval {} is zero", "index + 1")]
+    ZeroInterval { index: usize },
+    #[display(
+        fmt = "interval {} spans {} semitones, at most {} are allowed",
+        "index + 1",
+        interval,
+        MAX_INTERVAL
+    )]
+    IntervalTooLarge { index: usize, interval: usize },
+    #[display(fmt = "intervals add up to {} semitones instead of 12", sum)]
+    WrongSum { sum: usize },
+    #[display(fmt = "no known mode has the intervals {:?}", intervals)]
+    UnknownPattern {
+        #[error(not(source))]
+        intervals: Vec<usize>,
+    },
+    #[display(fmt = "no pitch classes given")]
+    Empty,
+    #[display(fmt = "no known mode has the pitch classes {:?}", pitch_classes)]
+    UnknownSet {
+        #[error(not(source))]
+        pitch_classes: Vec<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+#[display(
+    fmt = "invalid scale `{}`, expected a root and a mode such as `A dorian`",
+    input
+)]
+pub struct ParseScaleError {
+    #[error(not(source))]
+    pub input: String,
+}
+
+impl FromStr for Scale {
+    type Err = ParseScaleError;
+
+    /// `C major`, `C:major`, `F# melodic minor` or the `Display` form `F# MelodicMinor`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseScaleError {
+            input: s.to_string(),
+        };
+        let mut words = s
+            .split(|c: char| c.is_whitespace() || c == ':')
+            .filter(|word| !word.is_empty());
+        let start_note = words
+            .next()
+            .ok_or_else(error)?
+            .parse()
+            .map_err(|_| error())?;
+        let mode = words.collect::<Vec<_>>().join("-");
+        let mode = ScaleMode::from_str(&mode, true)
+            .ok()
+            .or_else(|| {
+                ScaleMode::iter()
+                    .find(|candidate| format!("{candidate:?}").eq_ignore_ascii_case(&mode))
+            })
+            .ok_or_else(error)?;
+        Ok(Self { start_note, mode })
+    }
+}
+
+/// which way a practice sequence runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Direction {
+    Ascending,
+    Descending,
+    /// up, then back down without repeating the top note
+    Both,
+}
+
+/// octave the root of a practice sequence sits in
+pub const PRACTICE_OCTAVE: i32 = 3;
+
+impl Display for Scale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { start_note, .. } = self;
+        write!(f, "{start_note} {}", self.mode_name())
+    }
+}
+
+impl Scale {
+    pub fn all() -> impl Iterator<Item = Self> {
+        Note::iter()
+            .flat_map(|start_note| ScaleMode::iter().map(move |mode| Self { start_note, mode }))
+    }
+
+    pub fn random(rng: &mut StdRng) -> Self {
+        let modes = ScaleMode::iter().collect::<Vec<_>>();
+        Self {
+            start_note: Note::from_index(rng.random_range(0..Note::COUNT)),
+            mode: *modes.choose(rng).expect("there are modes"),
+        }
+    }
+
+    pub fn mode_name(&self) -> String {
+        format!("{:?}", self.mode)
+    }
+
+    /// the scale rotated to start on its (1-based) `degree`, e.g. the 5th degree of C major
+    /// is G mixolydian; `None` for degree 0 and when the rotated pattern is not one of the
+    /// known modes
+    pub fn mode_of(&self, degree: usize) -> Option<Scale> {
+        let mut intervals = self.mode.intervals_raw();
+        let rotation = degree.checked_sub(1)? % intervals.len();
+        intervals.rotate_left(rotation);
+        Some(Scale {
+            start_note: self.degrees()[rotation],
+            mode: ScaleMode::from_intervals(&intervals)?,
+        })
+    }
+
+    /// the major scale with the same notes, `None` for modes that are not a rotation of major
+    pub fn parent_major(&self) -> Option<Scale> {
+        (1..=self.degrees().len())
+            .filter_map(|degree| self.mode_of(degree))
+            .find(|scale| matches!(scale.mode, ScaleMode::Major))
+    }
+
+    pub fn notes_list(&self) -> Vec<Note> {
+        let mut notes = vec![self.start_note];
+        let intervals = self.mode.intervals_raw();
+        intervals.iter().for_each(|interval| {
+            let latest = notes
+                .last()
+                .cloned()
+                .unwrap_or(self.start_note)
+                .offset_by(*interval as _);
+            notes.push(latest)
+        });
+        notes
+    }
+
+    pub fn descending_notes_list(&self) -> Vec<Note> {
+        let mut notes = vec![self.start_note];
+        let intervals = self.mode.descending_intervals_raw();
+        intervals.iter().rev().for_each(|interval| {
+            let latest = notes
+                .last()
+                .cloned()
+                .unwrap_or(self.start_note)
+                .offset_by(-(*interval as i32));
+            notes.push(latest)
+        });
+        notes
+    }
+
+    /// the scale notes without the closing octave, in scale order from the root; anything
+    /// printed or serialized lists notes through this rather than [Scale::notes]
+    pub fn degrees(&self) -> Vec<Note> {
+        let mut notes = self.notes_list();
+        if notes.len() > 1 && notes.first() == notes.last() {
+            notes.pop();
+        }
+        notes
+    }
+
+    /// stacks diatonic thirds on the given (1-based) degree of [Scale::diatonic_degrees]
+    pub fn chord(&self, degree: usize, size: ChordSize) -> Vec<Note> {
+        let degrees = self.diatonic_degrees();
+        (0..size.note_count())
+            .map(|third| degrees[(degree - 1 + third * 2) % degrees.len()])
+            .collect()
+    }
+
+    pub fn diatonic_chords(&self, size: ChordSize) -> Vec<Vec<Note>> {
+        (1..=self.diatonic_degrees().len())
+            .map(|degree| self.chord(degree, size))
+            .collect()
+    }
+
+    /// the scale pitches from the root in `start_octave` upwards, never ending
+    pub fn ascending_pitches(&self, start_octave: i32) -> impl Iterator<Item = Pitch> {
+        let root = Pitch::new(self.start_note, start_octave);
+        std::iter::once(root).chain(self.mode.intervals().scan(root, |pitch, interval| {
+            *pitch = pitch.offset_by(interval as _);
+            Some(*pitch)
+        }))
+    }
+
+    /// the scale over `octaves` octaves from the root at [PRACTICE_OCTAVE], the way down
+    /// honoring the descending form of the mode; every exporter plays this sequence
+    pub fn practice_sequence(&self, octaves: usize, direction: Direction) -> Vec<Pitch> {
+        let descending = self.mode.descending_intervals_raw().repeat(octaves);
+        let up = self
+            .ascending_pitches(PRACTICE_OCTAVE)
+            .take(self.mode.intervals_raw().len() * octaves + 1)
+            .collect::<Vec<_>>();
+        let top = *up.last().expect("it is not empty");
+        let down = descending
+            .iter()
+            .rev()
+            .fold(vec![top], |mut sequence, interval| {
+                let last = *sequence.last().expect("it is not empty");
+                sequence.push(last.offset_by(-(*interval as i32)));
+                sequence
+            });
+        match direction {
+            Direction::Ascending => up,
+            Direction::Descending => down,
+            Direction::Both => up.into_iter().chain(down.into_iter().skip(1)).collect(),
+        }
+    }
+
+    /// every degree as (note, 1-based degree, interval above the root such as `M3`), the
+    /// tritone named `A4` or `d5` by the degree it falls on
+    pub fn annotated_notes(&self) -> Vec<(Note, usize, String)> {
+        self.degrees()
+            .into_iter()
+            .enumerate()
+            .map(|(index, note)| {
+                let interval = Interval::from_semitones(self.start_note.ascending_distance(note));
+                let name = match (interval, index + 1) {
+                    (Interval::Tritone, 4) => "A4",
+                    (Interval::Tritone, 5) => "d5",
+                    (interval, _) => interval.short_name(),
+                };
+                (note, index + 1, name.to_string())
+            })
+            .collect()
+    }
+
+    /// the degrees (1-based) where the scale differs from the major scale on its root, or the
+    /// natural minor one when its third is minor: the notes giving a mode its color, such as
+    /// the raised 4th of lydian; empty for the reference scales and for other note counts
+    pub fn characteristic_degrees(&self) -> Vec<usize> {
+        let reference = Scale {
+            start_note: self.start_note,
+            mode: match self.has_minor_third() {
+                true => ScaleMode::Minor,
+                false => ScaleMode::Major,
+            },
+        };
+        let (ours, theirs) = (self.degrees(), reference.degrees());
+        match ours.len() == theirs.len() {
+            true => (1..=ours.len())
+                .filter(|degree| ours[degree - 1] != theirs[degree - 1])
+                .collect(),
+            false => vec![],
+        }
+    }
+
+    /// 1-based scale degree of `note`
+    pub fn degree_of(&self, note: Note) -> Option<usize> {
+        self.degrees()
+            .iter()
+            .position(|degree| *degree == note)
+            .map(|index| index + 1)
+    }
+
+    /// the degrees without a bebop passing note, the seven notes syllables and triads go by
+    pub fn diatonic_degrees(&self) -> Vec<Note> {
+        let passing = self.mode.passing_degree();
+        self.degrees()
+            .into_iter()
+            .enumerate()
+            .filter(|(degree, _)| Some(*degree) != passing)
+            .map(|(_, note)| note)
+            .collect()
+    }
+
+    /// movable do syllable of a scale note, altered against the same degree of the major
+    /// scale, e.g. Me for the minor third; a bebop passing note gets its chromatic syllable
+    pub fn solfege(&self, note: Note) -> Option<&'static str> {
+        const MAJOR: [(usize, &str); 7] = [
+            (0, "Do"),
+            (2, "Re"),
+            (4, "Mi"),
+            (5, "Fa"),
+            (7, "Sol"),
+            (9, "La"),
+            (11, "Ti"),
+        ];
+        const CHROMATIC: [&str; 12] = [
+            "Do", "Di", "Re", "Ri", "Mi", "Fa", "Fi", "Sol", "Si", "La", "Li", "Ti",
+        ];
+        self.degree_of(note)?;
+        let semitones = self.start_note.ascending_distance(note);
+        let Some(step) = self
+            .diatonic_degrees()
+            .iter()
+            .position(|degree| *degree == note)
+        else {
+            return Some(CHROMATIC[semitones]);
+        };
+        let (major, syllable) = MAJOR.get(step).copied()?;
+        let altered = match (semitones as i32 - major as i32, syllable) {
+            (0, syllable) => syllable,
+            (1, "Do") => "Di",
+            (1, "Re") => "Ri",
+            (1, "Fa") => "Fi",
+            (1, "Sol") => "Si",
+            (1, "La") => "Li",
+            (-1, "Re") => "Ra",
+            (-1, "Mi") => "Me",
+            (-1, "Sol") => "Se",
+            (-1, "La") => "Le",
+            (-1, "Ti") => "Te",
+            _ => CHROMATIC[semitones],
+        };
+        Some(altered)
+    }
+
+    /// roman numeral, root and quality of the triad on every degree; triads that are not
+    /// two stacked thirds (possible in the harmonic scales) have no quality and an upper
+    /// case numeral; a bebop passing note gets no triad
+    pub fn degree_triads(&self) -> Vec<(String, Note, Option<ChordQuality>)> {
+        self.diatonic_chords(ChordSize::Triad)
+            .iter()
+            .enumerate()
+            .map(|(index, triad)| {
+                let quality = ChordQuality::of_triad(triad);
+                let numeral = match quality {
+                    Some(quality) => quality.numeral(index + 1),
+                    None => roman_numeral(index + 1).to_string(),
+                };
+                (numeral, triad[0], quality)
+            })
+            .collect()
+    }
+
+    /// for membership checks only, the iteration order is arbitrary
+    pub fn notes(&self) -> HashSet<Note> {
+        self.notes_list().into_iter().collect()
+    }
+
+    pub fn bitmask(&self) -> u16 {
+        note_bitmask(self.notes_list())
+    }
+
+    /// every note mirrored around `axis`, as many semitones below it as it was above; major
+    /// around its root turns into phrygian
+    pub fn invert(&self, axis: Note) -> HashSet<Note> {
+        self.notes()
+            .into_iter()
+            .map(|note| note.mirror(axis))
+            .collect()
+    }
+
+    /// the scale rooted on `start_note` walking `intervals`, checking each step is
+    /// between 1 and [MAX_INTERVAL] semitones and that they add up to an octave
+    pub fn try_from_intervals(start_note: Note, intervals: &[usize]) -> Result<Self, ScaleError> {
+        for (index, interval) in intervals.iter().copied().enumerate() {
+            match interval {
+                0 => return Err(ScaleError::ZeroInterval { index }),
+                _ if interval > MAX_INTERVAL => {
+                    return Err(ScaleError::IntervalTooLarge { index, interval })
+                }
+                _ => {}
+            }
+        }
+        let sum = intervals.iter().sum::<usize>();
+        if sum != Note::COUNT {
+            return Err(ScaleError::WrongSum { sum });
+        }
+        let mode =
+            ScaleMode::from_intervals(intervals).ok_or_else(|| ScaleError::UnknownPattern {
+                intervals: intervals.to_vec(),
+            })?;
+        Ok(Self { start_note, mode })
+    }
+
+    /// the scale made of these pitch classes (0 is C, taken modulo 12) rooted on the lowest one
+    pub fn from_pitch_classes(pitch_classes: &[usize]) -> Result<Self, ScaleError> {
+        let classes = pitch_classes
+            .iter()
+            .map(|class| class % Note::COUNT)
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+        let lowest = classes.first().copied().ok_or(ScaleError::Empty)?;
+        let mask = note_bitmask(classes.iter().copied().map(Note::from_index));
+        Self::from_bitmask(Note::from_index(lowest), mask).ok_or(ScaleError::UnknownSet {
+            pitch_classes: classes,
+        })
+    }
+
+    pub fn from_bitmask(start_note: Note, mask: u16) -> Option<Self> {
+        ScaleMode::iter()
+            .map(|mode| Self { start_note, mode })
+            .find(|scale| scale.bitmask() == mask)
+    }
+
+    /// whether both scales use the same notes, i.e. they are modes of each other
+    pub fn is_mode_of(&self, other: &Scale) -> bool {
+        self.bitmask() == other.bitmask()
+    }
+
+    pub fn contains_all(&self, mask: u16) -> bool {
+        self.bitmask() & mask == mask
+    }
+
+    pub fn has_minor_third(&self) -> bool {
+        let notes = self.notes();
+        notes.contains(&self.start_note.offset_by(3))
+            && !notes.contains(&self.start_note.offset_by(4))
+    }
+
+    pub fn is_diatonic(&self, chord: &[Note]) -> bool {
+        self.contains_all(note_bitmask(chord.iter().copied()))
+    }
+
+    /// for a chord outside the scale, the closest scale it can be borrowed from:
+    /// the parallel major/minor, then the other parallel modes, then the keys a fifth away
+    pub fn borrowed_from(&self, chord: &[Note]) -> Option<Scale> {
+        if self.is_diatonic(chord) {
+            return None;
+        }
+        let parallel = |mode| Scale {
+            start_note: self.start_note,
+            mode,
+        };
+        let opposite = match self.has_minor_third() {
+            true => ScaleMode::Major,
+            false => ScaleMode::Minor,
+        };
+        let neighbor = |offset| Scale {
+            start_note: self.start_note.offset_by(offset),
+            mode: self.mode,
+        };
+        std::iter::once(parallel(opposite))
+            .chain(ScaleMode::iter().map(parallel))
+            .chain([neighbor(7), neighbor(5)])
+            .find(|candidate| candidate.is_diatonic(chord))
+    }
+}
+
+/// `sequence` with a chromatic passing tone between every two neighbours a whole step apart,
+/// each pitch paired with whether it is such an added passing tone
+pub fn chromatic_approaches(sequence: &[Pitch]) -> Vec<(Pitch, bool)> {
+    let mut approached = sequence
+        .iter()
+        .tuple_windows()
+        .flat_map(|(from, to)| {
+            let step = to.semitones() - from.semitones();
+            let passing = (step.abs() == 2).then(|| (from.offset_by(step / 2), true));
+            std::iter::once((*from, false)).chain(passing)
+        })
+        .collect::<Vec<_>>();
+    approached.extend(sequence.last().map(|last| (*last, false)));
+    approached
+}
+
+/// the scale degrees comma separated, for shell pipelines
+pub fn dump_notes(scale: &Scale) -> String {
+    scale
+        .degrees()
+        .iter()
+        .map(|note| note.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// `W` for a whole step, `H` for a half step and e.g. `3H` for anything wider
+fn step_name(semitones: usize) -> String {
+    match semitones {
+        1 => "H".to_string(),
+        2 => "W".to_string(),
+        other => format!("{other}H"),
+    }
+}
+
+/// the construction of the scale in a sentence, for beginners
+pub fn explain(scale: &Scale) -> String {
+    let steps = scale
+        .mode
+        .intervals_raw()
+        .into_iter()
+        .map(step_name)
+        .collect::<Vec<_>>()
+        .join("-");
+    let notes = scale
+        .degrees()
+        .iter()
+        .map(|note| note.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "{scale} is built from the intervals {steps} starting on {}, giving {notes}.",
+        scale.start_note
+    )
+}
+
+/// every mode by its command line name with its interval pattern
+pub fn list_modes() -> String {
+    ScaleMode::iter()
+        .map(|mode| {
+            let name = mode
+                .to_possible_value()
+                .expect("no modes are skipped")
+                .get_name()
+                .to_string();
+            let intervals = mode
+                .intervals_raw()
+                .iter()
+                .map(|interval| interval.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{name}\t{intervals}\n")
+        })
+        .collect()
+}
+
+/// every scale containing all of `notes`, roots in circle of fifths order from C and the
+/// modes on each root in the order they are declared
+pub fn scales_containing(notes: impl IntoIterator<Item = Note>) -> Vec<Scale> {
+    let mask = note_bitmask(notes);
+    #[cfg(feature = "rayon")]
+    let scales = scales_containing_mask_parallel(mask);
+    #[cfg(not(feature = "rayon"))]
+    let scales = scales_containing_mask(mask);
+    scales
+        .into_iter()
+        .sorted_by_key(|scale| (scale.start_note.fifths_from_c(), scale.mode as usize))
+        .collect()
+}
+
+/// every scale containing all notes of a [note_bitmask], one root and mode at a time
+pub fn scales_containing_mask(mask: u16) -> Vec<Scale> {
+    Scale::all()
+        .filter(|scale| scale.contains_all(mask))
+        .collect()
+}
+
+/// [scales_containing_mask] checking roots and modes on the rayon thread pool, in the
+/// same order
+#[cfg(feature = "rayon")]
+pub fn scales_containing_mask_parallel(mask: u16) -> Vec<Scale> {
+    use rayon::prelude::*;
+    Scale::all()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .filter(|scale| scale.contains_all(mask))
+        .collect()
+}
+
+/// shared notes, notes unique to either scale and the share of all their notes in common
+pub fn compare_scales(a: &Scale, b: &Scale) -> String {
+    let (a_mask, b_mask) = (a.bitmask(), b.bitmask());
+    let list = |mask| {
+        notes_of_bitmask(mask)
+            .iter()
+            .map(|note| note.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let similarity = 100 * (a_mask & b_mask).count_ones() / (a_mask | b_mask).count_ones();
+    format!(
+        "SHARED: {}\nONLY IN {a}: {}\nONLY IN {b}: {}\nSIMILARITY: {similarity}%\n",
+        list(a_mask & b_mask),
+        list(a_mask & !b_mask),
+        list(b_mask & !a_mask),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_bitmask() {
+        let c_major = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        assert_eq!(c_major.bitmask().count_ones(), 7);
+        assert_eq!(c_major.bitmask(), 0b1010_1011_0101);
+        let c_triad = note_bitmask([Note::C, Note::E, Note::G]);
+        assert_eq!(c_major.bitmask() & c_triad, c_triad);
+        assert!(c_major.contains_all(c_triad));
+        assert!(!c_major.contains_all(note_bitmask([Note::Cs])));
+        let from_mask = Scale::from_bitmask(Note::C, c_major.bitmask()).unwrap();
+        assert_eq!(from_mask.notes(), c_major.notes());
+        assert!(Scale::from_bitmask(Note::C, c_triad).is_none());
+    }
+
+    #[test]
+    fn test_greek_mode_aliases() {
+        let parse = |name| ScaleMode::from_str(name, true).unwrap();
+        assert!(matches!(parse("ionian"), ScaleMode::Major));
+        assert!(matches!(parse("major"), ScaleMode::Major));
+        assert!(matches!(parse("aeolian"), ScaleMode::Minor));
+        assert!(matches!(parse("mixolydian"), ScaleMode::Mixolydian));
+        for mode in ScaleMode::value_variants() {
+            assert_eq!(mode.intervals_raw().iter().sum::<usize>(), 12);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        for &mode in ScaleMode::value_variants() {
+            let json = serde_json::to_string(&mode).unwrap();
+            let name = mode.to_possible_value().unwrap().get_name().to_string();
+            assert_eq!(json, format!("\"{name}\""));
+            let parsed: ScaleMode = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed.intervals_raw(), mode.intervals_raw());
+        }
+        let scale = Scale {
+            start_note: Note::Fs,
+            mode: ScaleMode::MelodicMinor,
+        };
+        let json = serde_json::to_string(&scale).unwrap();
+        assert_eq!(json, r#"{"start_note":"F#","mode":"melodic-minor"}"#);
+        let parsed: Scale = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.notes_list(), scale.notes_list());
+    }
+
+    #[test]
+    fn test_exotic_scales() {
+        use Note::*;
+        for mode in [
+            ScaleMode::HungarianMinor,
+            ScaleMode::NeapolitanMinor,
+            ScaleMode::PhrygianDominant,
+            ScaleMode::HarmonicMajor,
+        ] {
+            let scale = Scale {
+                start_note: C,
+                mode,
+            };
+            assert_eq!(scale.degrees().len(), 7);
+            assert_eq!(scale.notes().len(), 7);
+        }
+        let e_phrygian_dominant = Scale {
+            start_note: E,
+            mode: ScaleMode::PhrygianDominant,
+        };
+        assert_eq!(e_phrygian_dominant.degrees(), vec![E, F, Gs, A, B, C, D]);
+        let a_hungarian = Scale {
+            start_note: A,
+            mode: ScaleMode::HungarianMinor,
+        };
+        assert_eq!(a_hungarian.degrees(), vec![A, B, C, Ds, E, F, Gs]);
+    }
+
+    #[test]
+    fn test_borrowed_chords() {
+        use Note::*;
+        let c_major = Scale {
+            start_note: C,
+            mode: ScaleMode::Major,
+        };
+        let f_minor = [F, Gs, C];
+        assert!(!c_major.is_diatonic(&f_minor));
+        let borrowed = c_major.borrowed_from(&f_minor).unwrap();
+        assert_eq!(borrowed.to_string(), "C Minor");
+        assert!(c_major.is_diatonic(&[D, F, A]));
+        assert!(c_major.borrowed_from(&[D, F, A]).is_none());
+        let d_major = [D, Fs, A];
+        assert_eq!(
+            c_major.borrowed_from(&d_major).unwrap().to_string(),
+            "C Lydian"
+        );
+    }
+
+    #[test]
+    fn test_random_scale_is_seeded() {
+        use crate::random::rng_from_seed;
+        let scale = |seed| Scale::random(&mut rng_from_seed(Some(seed)));
+        assert_eq!(scale(42).to_string(), scale(42).to_string());
+        let seen = (0..200)
+            .map(|seed| format!("{:?}", scale(seed).mode))
+            .collect::<HashSet<_>>();
+        assert_eq!(seen.len(), ScaleMode::iter().count());
+        assert_eq!(ScaleMode::iter().count(), ScaleMode::value_variants().len());
+    }
+
+    #[test]
+    fn test_list_modes() {
+        assert_eq!(ScaleMode::iter().count(), ScaleMode::COUNT);
+        assert_eq!(ScaleMode::COUNT, ScaleMode::value_variants().len());
+        let listing = list_modes();
+        assert_eq!(listing.lines().count(), ScaleMode::COUNT);
+        assert!(listing.starts_with("major\t2 2 1 2 2 2 1\n"));
+        assert!(listing.contains("phrygian-dominant\t1 3 1 2 1 2 2\n"));
+    }
+
+    #[test]
+    fn test_chord_sizes() {
+        use Note::*;
+        let c_major = Scale {
+            start_note: C,
+            mode: ScaleMode::Major,
+        };
+        assert_eq!(c_major.chord(1, ChordSize::Triad), vec![C, E, G]);
+        assert_eq!(c_major.chord(1, ChordSize::Seventh), vec![C, E, G, B]);
+        assert_eq!(c_major.chord(1, ChordSize::Ninth), vec![C, E, G, B, D]);
+        assert_eq!(c_major.chord(5, ChordSize::Seventh), vec![G, B, D, F]);
+        assert_eq!(c_major.chord(7, ChordSize::Triad), vec![B, D, F]);
+        assert_eq!(c_major.diatonic_chords(ChordSize::Triad).len(), 7);
+    }
+
+    #[test]
+    fn test_scales_containing() {
+        use Note::*;
+        let found = scales_containing([C, E, G]);
+        assert!(found
+            .iter()
+            .all(|scale| [C, E, G].iter().all(|n| scale.notes().contains(n))));
+        assert!(found
+            .iter()
+            .any(|scale| scale.start_note == G && matches!(scale.mode, ScaleMode::Major)));
+        assert_eq!(scales_containing(Note::iter()).len(), 0);
+    }
+
+    #[test]
+    fn test_bebop_scales() {
+        use Note::*;
+        let dominant = "G bebop-dominant".parse::<Scale>().unwrap();
+        assert_eq!(dominant.degrees(), [G, A, B, C, D, E, F, Fs]);
+        assert!(dominant.notes().contains(&F) && dominant.notes().contains(&Fs));
+        assert_eq!(dominant.notes_list().len(), 9);
+        let triads = dominant.degree_triads();
+        assert_eq!(triads.len(), 7);
+        assert_eq!(triads[4], ("v".to_string(), D, Some(ChordQuality::Minor)));
+        let major = "C bebop-major".parse::<Scale>().unwrap();
+        assert_eq!(major.degrees(), [C, D, E, F, G, Gs, A, B]);
+        assert_eq!(major.characteristic_degrees(), Vec::<usize>::new());
+        assert_eq!(major.solfege(Gs), Some("Si"));
+        assert_eq!(major.solfege(B), Some("Ti"));
+        assert_eq!(major.solfege(A), Some("La"));
+        assert_eq!(dominant.solfege(F), Some("Te"));
+        assert_eq!(dominant.solfege(Fs), Some("Ti"));
+        let numerals = major
+            .degree_triads()
+            .into_iter()
+            .map(|(numeral, ..)| numeral)
+            .collect::<Vec<_>>();
+        assert_eq!(numerals, ["I", "ii", "iii", "IV", "V", "vi", "vii°"]);
+    }
+
+    #[test]
+    fn test_bebop_diatonic_chords() {
+        let dominant = "G bebop-dominant".parse::<Scale>().unwrap();
+        let mixolydian = "G mixolydian".parse::<Scale>().unwrap();
+        for size in [ChordSize::Triad, ChordSize::Seventh] {
+            assert_eq!(
+                dominant.diatonic_chords(size),
+                mixolydian.diatonic_chords(size)
+            );
+        }
+        let major = "C bebop-major".parse::<Scale>().unwrap();
+        assert_eq!(
+            major.chord(1, ChordSize::Seventh),
+            [Note::C, Note::E, Note::G, Note::B]
+        );
+    }
+
+    #[test]
+    fn test_chromatic_approaches() {
+        let c_major = "C major".parse::<Scale>().unwrap();
+        let sequence = c_major.practice_sequence(1, Direction::Both);
+        let approached = chromatic_approaches(&sequence);
+        assert_eq!(approached.len(), sequence.len() + 10);
+        assert_eq!(
+            approached[..4],
+            [
+                (Pitch::new(Note::C, PRACTICE_OCTAVE), false),
+                (Pitch::new(Note::Cs, PRACTICE_OCTAVE), true),
+                (Pitch::new(Note::D, PRACTICE_OCTAVE), false),
+                (Pitch::new(Note::Ds, PRACTICE_OCTAVE), true),
+            ]
+        );
+        assert_eq!(approached[5], (Pitch::new(Note::F, PRACTICE_OCTAVE), false));
+        let originals = approached
+            .iter()
+            .filter(|(_, passing)| !passing)
+            .map(|(pitch, _)| *pitch)
+            .collect::<Vec<_>>();
+        assert_eq!(originals, sequence);
+        assert!(chromatic_approaches(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_scales_containing_order() {
+        use Note::*;
+        let found = scales_containing([C, E, G]);
+        assert_eq!(found, scales_containing([G, C, E]));
+        assert_eq!(
+            found[..2],
+            ["C major", "C lydian"].map(|s| s.parse().unwrap())
+        );
+        let roots = found
+            .iter()
+            .map(|scale| scale.start_note)
+            .dedup()
+            .collect::<Vec<_>>();
+        assert_eq!(roots[..4], [C, G, D, A]);
+        assert_eq!(roots.last(), Some(&F));
+        assert!(found
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a.start_note != b.start_note || (a.mode as usize) < (b.mode as usize)));
+    }
+
+    #[test]
+    fn test_melodic_minor_descends_natural() {
+        use Note::*;
+        let a_melodic = Scale {
+            start_note: A,
+            mode: ScaleMode::MelodicMinor,
+        };
+        assert_eq!(a_melodic.notes_list(), vec![A, B, C, D, E, Fs, Gs, A]);
+        assert_eq!(
+            a_melodic.descending_notes_list(),
+            vec![A, G, F, E, D, C, B, A]
+        );
+        let c_major = Scale {
+            start_note: C,
+            mode: ScaleMode::Major,
+        };
+        let mut descending = c_major.descending_notes_list();
+        descending.reverse();
+        assert_eq!(descending, c_major.notes_list());
+    }
+
+    #[test]
+    fn test_practice_sequence() {
+        use Note::*;
+        let c_major = Scale {
+            start_note: C,
+            mode: ScaleMode::Major,
+        };
+        let sequence = c_major.practice_sequence(1, Direction::Both);
+        let expected = [C, D, E, F, G, A, B, C, B, A, G, F, E, D, C]
+            .iter()
+            .enumerate()
+            .map(
+                |(index, note)| match (1..=13).contains(&index) && *note == C {
+                    true => Pitch::new(*note, PRACTICE_OCTAVE + 1),
+                    false => Pitch::new(*note, PRACTICE_OCTAVE),
+                },
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(sequence, expected);
+        let up = c_major.practice_sequence(2, Direction::Ascending);
+        assert_eq!(up.len(), 15);
+        assert_eq!(up.last(), Some(&Pitch::new(C, PRACTICE_OCTAVE + 2)));
+        let down = c_major.practice_sequence(2, Direction::Descending);
+        assert_eq!(down, up.into_iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_parse_scale() {
+        let scale = "A dorian".parse::<Scale>().unwrap();
+        assert_eq!(scale.start_note, Note::A);
+        assert!(matches!(scale.mode, ScaleMode::Dorian));
+        let scale = "Db melodic minor".parse::<Scale>().unwrap();
+        assert_eq!(scale.start_note, Note::Cs);
+        assert!(matches!(scale.mode, ScaleMode::MelodicMinor));
+        let roundtrip = scale.to_string().parse::<Scale>().unwrap();
+        assert!(matches!(roundtrip.mode, ScaleMode::MelodicMinor));
+        assert!("C".parse::<Scale>().is_err());
+        assert!("H major".parse::<Scale>().is_err());
+        assert!("C blues".parse::<Scale>().is_err());
+    }
+
+    #[test]
+    fn test_mode_of() {
+        let c_major = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let fifth = c_major.mode_of(5).unwrap();
+        assert_eq!(fifth.to_string(), "G Mixolydian");
+        assert_eq!(fifth.notes(), c_major.notes());
+        assert_eq!(c_major.mode_of(6).unwrap().to_string(), "A Minor");
+        assert_eq!(c_major.mode_of(1).unwrap().to_string(), "C Major");
+        let melodic = Scale {
+            start_note: Note::A,
+            mode: ScaleMode::MelodicMinor,
+        };
+        assert!(melodic.mode_of(2).is_none());
+        assert!(c_major.mode_of(0).is_none());
+    }
+
+    #[test]
+    fn test_compare_scales() {
+        let scale = |s: &str| s.parse::<Scale>().unwrap();
+        let related = compare_scales(&scale("C:major"), &scale("A:minor"));
+        assert!(related.contains("SHARED: C, D, E, F, G, A, B\n"));
+        assert!(related.contains("ONLY IN C Major: \n"));
+        assert!(related.ends_with("SIMILARITY: 100%\n"));
+        let parallel = compare_scales(&scale("C:major"), &scale("C:minor"));
+        assert!(parallel.contains("SHARED: C, D, F, G\n"));
+        assert!(parallel.contains("ONLY IN C Major: E, A, B\n"));
+        assert!(parallel.contains("ONLY IN C Minor: D#, G#, A#\n"));
+        assert!(parallel.ends_with("SIMILARITY: 40%\n"));
+    }
+
+    #[test]
+    fn test_solfege() {
+        let syllables = |scale: &str| {
+            let scale = scale.parse::<Scale>().unwrap();
+            scale
+                .degrees()
+                .iter()
+                .map(|note| scale.solfege(*note).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            syllables("C major"),
+            vec!["Do", "Re", "Mi", "Fa", "Sol", "La", "Ti"]
+        );
+        assert_eq!(
+            syllables("A minor"),
+            vec!["Do", "Re", "Me", "Fa", "Sol", "Le", "Te"]
+        );
+        assert_eq!(syllables("F lydian")[3], "Fi");
+        assert_eq!(syllables("E phrygian")[1], "Ra");
+        let c_major = "C major".parse::<Scale>().unwrap();
+        assert_eq!(c_major.degree_of(Note::G), Some(5));
+        assert_eq!(c_major.solfege(Note::Cs), None);
+    }
+
+    #[test]
+    fn test_degree_triads() {
+        let c_major = "C major".parse::<Scale>().unwrap();
+        let triads = c_major.degree_triads();
+        assert_eq!(triads.len(), 7);
+        assert_eq!(
+            triads[1],
+            ("ii".to_string(), Note::D, Some(ChordQuality::Minor))
+        );
+        assert_eq!(
+            triads
+                .iter()
+                .map(|(numeral, ..)| numeral.as_str())
+                .collect::<Vec<_>>(),
+            vec!["I", "ii", "iii", "IV", "V", "vi", "vii°"]
+        );
+        let harmonic = "A harmonic major".parse::<Scale>().unwrap();
+        assert_eq!(harmonic.degree_triads()[5].0, "VI+");
+    }
+
+    #[test]
+    fn test_parent_major() {
+        let parent = "A dorian".parse::<Scale>().unwrap().parent_major().unwrap();
+        assert_eq!(parent.to_string(), "G Major");
+        let parent = "E minor".parse::<Scale>().unwrap().parent_major().unwrap();
+        assert_eq!(parent.to_string(), "G Major");
+        let c_major = "C major".parse::<Scale>().unwrap();
+        assert_eq!(c_major.parent_major().unwrap().to_string(), "C Major");
+        assert!("A melodic minor"
+            .parse::<Scale>()
+            .unwrap()
+            .parent_major()
+            .is_none());
+    }
+
+    #[test]
+    fn test_dump_notes() {
+        let c_major = "C major".parse::<Scale>().unwrap();
+        assert_eq!(dump_notes(&c_major), "C,D,E,F,G,A,B");
+        let a_minor = "A minor".parse::<Scale>().unwrap();
+        assert_eq!(dump_notes(&a_minor), "A,B,C,D,E,F,G");
+    }
+
+    #[test]
+    fn test_try_from_intervals() {
+        let scale = Scale::try_from_intervals(Note::D, &[2, 1, 2, 2, 2, 1, 2]).unwrap();
+        assert_eq!(scale.start_note, Note::D);
+        assert!(matches!(scale.mode, ScaleMode::Dorian));
+        assert_eq!(
+            Scale::try_from_intervals(Note::C, &[2, 2, 1, 2, 2, 2]).unwrap_err(),
+            ScaleError::WrongSum { sum: 11 }
+        );
+        assert_eq!(
+            Scale::try_from_intervals(Note::C, &[2, 0, 3, 2, 2, 2, 1]).unwrap_err(),
+            ScaleError::ZeroInterval { index: 1 }
+        );
+        assert_eq!(
+            Scale::try_from_intervals(Note::C, &[7, 5]).unwrap_err(),
+            ScaleError::IntervalTooLarge {
+                index: 0,
+                interval: 7
+            }
+        );
+        assert!(matches!(
+            Scale::try_from_intervals(Note::C, &[3, 2, 2, 3, 2]),
+            Err(ScaleError::UnknownPattern { .. })
+        ));
+        assert_eq!(
+            ScaleError::WrongSum { sum: 11 }.to_string(),
+            "intervals add up to 11 semitones instead of 12"
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let scale = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        assert_eq!(
+            explain(&scale),
+            "C Major is built from the intervals W-W-H-W-W-W-H starting on C, giving C D E F G A B."
+        );
+        let scale = Scale {
+            start_note: Note::A,
+            mode: ScaleMode::HungarianMinor,
+        };
+        assert!(explain(&scale).contains("W-H-3H-H-H-3H-H"));
+    }
+
+    #[test]
+    fn test_is_mode_of() {
+        let c_major = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let d_dorian = Scale {
+            start_note: Note::D,
+            mode: ScaleMode::Dorian,
+        };
+        let c_minor = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Minor,
+        };
+        assert!(d_dorian.is_mode_of(&c_major));
+        assert!(c_major.is_mode_of(&d_dorian));
+        assert!(!c_major.is_mode_of(&c_minor));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_search_matches_serial() {
+        for mask in [0, 0b1, 0b10010001, 0b101010110101, 0xfff] {
+            let describe =
+                |scales: Vec<Scale>| scales.iter().map(Scale::to_string).collect::<Vec<_>>();
+            assert_eq!(
+                describe(scales_containing_mask_parallel(mask)),
+                describe(scales_containing_mask(mask))
+            );
+        }
+    }
+
+    #[test]
+    fn test_annotated_notes() {
+        let c_major = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let annotated = c_major.annotated_notes();
+        assert_eq!(annotated.len(), 7);
+        assert_eq!(annotated[0], (Note::C, 1, "P1".to_string()));
+        assert_eq!(annotated[2], (Note::E, 3, "M3".to_string()));
+        assert_eq!(annotated[6], (Note::B, 7, "M7".to_string()));
+        let b_locrian = Scale {
+            start_note: Note::B,
+            mode: ScaleMode::Locrian,
+        };
+        assert_eq!(
+            b_locrian.annotated_notes()[4],
+            (Note::F, 5, "d5".to_string())
+        );
+        let f_lydian = Scale {
+            start_note: Note::F,
+            mode: ScaleMode::Lydian,
+        };
+        assert_eq!(
+            f_lydian.annotated_notes()[3],
+            (Note::B, 4, "A4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ascending_pitches() {
+        let c_major = Scale {
+            start_note: Note::C,
+            mode: ScaleMode::Major,
+        };
+        let pitches = c_major.ascending_pitches(4).take(15).collect::<Vec<_>>();
+        assert_eq!(pitches[0], Pitch::new(Note::C, 4));
+        assert_eq!(pitches[6], Pitch::new(Note::B, 4));
+        assert_eq!(pitches[7], Pitch::new(Note::C, 5));
+        assert_eq!(pitches[14], Pitch::new(Note::C, 6));
+        assert!(pitches.windows(2).all(|pair| pair[0] < pair[1]));
+        let notes = pitches.iter().map(|pitch| pitch.note).collect::<Vec<_>>();
+        assert_eq!(&notes[..8], c_major.notes_list().as_slice());
+        assert_eq!(&notes[7..], c_major.notes_list().as_slice());
+    }
+
+    #[test]
+    fn test_degrees_in_scale_order() {
+        for scale in Scale::all() {
+            let distances = scale
+                .degrees()
+                .iter()
+                .map(|note| scale.start_note.ascending_distance(*note))
+                .collect::<Vec<_>>();
+            assert!(distances.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_scales_dedupe_in_sets() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        let hash = |scale: &Scale| {
+            let mut hasher = DefaultHasher::new();
+            scale.hash(&mut hasher);
+            hasher.finish()
+        };
+        let a_minor: Scale = "A minor".parse().unwrap();
+        let a_aeolian: Scale = "A aeolian".parse().unwrap();
+        assert_eq!(a_minor, a_aeolian);
+        assert_eq!(hash(&a_minor), hash(&a_aeolian));
+        let c_major: Scale = "C major".parse().unwrap();
+        assert_ne!(a_minor, c_major);
+        let set = [a_minor, a_aeolian, c_major]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_from_pitch_classes() {
+        let scale = Scale::from_pitch_classes(&[0, 2, 4, 5, 7, 9, 11]).unwrap();
+        let c_major: Scale = "C major".parse().unwrap();
+        assert_eq!(scale.notes(), c_major.notes());
+        assert_eq!(scale, c_major);
+        let scale = Scale::from_pitch_classes(&[21, 11, 0, 2, 4, 5, 7, 9]).unwrap();
+        assert_eq!(scale, "C major".parse().unwrap());
+        let scale = Scale::from_pitch_classes(&[2, 4, 6, 7, 9, 11, 1]).unwrap();
+        assert_eq!(scale, "C# locrian".parse().unwrap());
+        let scale = Scale::from_pitch_classes(&[0, 2, 4, 5, 7, 9, 10, 11]).unwrap();
+        assert_eq!(scale, "C bebop-dominant".parse().unwrap());
+        assert_eq!(
+            Scale::from_pitch_classes(&[0, 3, 5, 7, 10]),
+            Err(ScaleError::UnknownSet {
+                pitch_classes: vec![0, 3, 5, 7, 10]
+            })
+        );
+        assert_eq!(
+            Scale::from_pitch_classes(&[19, 0]),
+            Err(ScaleError::UnknownSet {
+                pitch_classes: vec![0, 7]
+            })
+        );
+        assert_eq!(Scale::from_pitch_classes(&[]), Err(ScaleError::Empty));
+        assert_eq!(ScaleError::Empty.to_string(), "no pitch classes given");
+    }
+
+    #[test]
+    fn test_characteristic_degrees() {
+        let degrees = |s: &str| s.parse::<Scale>().unwrap().characteristic_degrees();
+        assert_eq!(degrees("C major"), Vec::<usize>::new());
+        assert_eq!(degrees("D dorian"), [6]);
+        assert_eq!(degrees("E phrygian"), [2]);
+        assert_eq!(degrees("F lydian"), [4]);
+        assert_eq!(degrees("G mixolydian"), [7]);
+        assert_eq!(degrees("B locrian"), [2, 5]);
+    }
+
+    #[test]
+    fn test_invert() {
+        let scale = "C major".parse::<Scale>().unwrap();
+        assert_eq!(
+            scale.invert(Note::C),
+            "C phrygian".parse::<Scale>().unwrap().notes()
+        );
+        assert_eq!(
+            scale.invert(Note::D),
+            "E phrygian".parse::<Scale>().unwrap().notes()
+        );
+        let scale = "A harmonic major".parse::<Scale>().unwrap();
+        assert_eq!(scale.invert(Note::C).len(), 7);
+    }
+}
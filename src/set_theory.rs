@@ -0,0 +1,103 @@
+use crate::note::Note;
+use std::collections::HashSet;
+use strum::EnumCount;
+
+/// pitch classes in ascending order, 0 being C
+fn pitch_classes(notes: &HashSet<Note>) -> Vec<usize> {
+    let mut classes = notes.iter().map(|note| note.index()).collect::<Vec<_>>();
+    classes.sort_unstable();
+    classes
+}
+
+/// what makes one ordering more compact than another: the span from the first class to the
+/// last, then to the one before the last and so on (Rahn's rule)
+fn packing(ordering: &[usize]) -> Vec<usize> {
+    let first = ordering.first().copied().unwrap_or(0);
+    ordering
+        .iter()
+        .rev()
+        .map(|class| (class + Note::COUNT - first) % Note::COUNT)
+        .collect()
+}
+
+/// the most compact rotation of the ascending classes, ties going to the lowest first class
+fn most_compact(classes: &[usize]) -> Vec<usize> {
+    (0..classes.len())
+        .map(|start| {
+            let mut rotation = classes.to_vec();
+            rotation.rotate_left(start);
+            rotation
+        })
+        .min_by_key(|rotation| (packing(rotation), rotation.first().copied()))
+        .unwrap_or_default()
+}
+
+/// the pitch classes (0 to 11, 0 being C) of `notes` in their most compact ascending
+/// rotation, e.g. `[9, 0, 4]` for A minor
+pub fn normal_form(notes: &HashSet<Note>) -> Vec<usize> {
+    most_compact(&pitch_classes(notes))
+}
+
+/// the normal form of `notes` or of their inversion, whichever packs tighter, moved to start
+/// on 0; sets related by transposition or inversion share it, so both triads are `[0, 3, 7]`
+pub fn prime_form(notes: &HashSet<Note>) -> Vec<usize> {
+    let from_zero = |normal: Vec<usize>| {
+        let first = normal.first().copied().unwrap_or(0);
+        normal
+            .iter()
+            .map(|class| (class + Note::COUNT - first) % Note::COUNT)
+            .collect::<Vec<_>>()
+    };
+    let inverted = notes
+        .iter()
+        .map(|note| note.mirror(Note::C))
+        .collect::<HashSet<_>>();
+    [normal_form(notes), normal_form(&inverted)]
+        .into_iter()
+        .map(from_zero)
+        .min_by_key(|form| form.iter().rev().copied().collect::<Vec<_>>())
+        .unwrap_or_default()
+}
+
+/// how many pairs of `notes` lie each interval class apart, from the minor second (or major
+/// seventh) up to the tritone; the major scale gives `[2, 5, 4, 3, 6, 1]`
+pub fn interval_vector(notes: &HashSet<Note>) -> [usize; 6] {
+    let classes = pitch_classes(notes);
+    let mut vector = [0; 6];
+    for (index, low) in classes.iter().enumerate() {
+        for high in &classes[index + 1..] {
+            let interval = high - low;
+            vector[interval.min(Note::COUNT - interval) - 1] += 1;
+        }
+    }
+    vector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Note::*;
+
+    #[test]
+    fn test_normal_and_prime_forms() {
+        let set = |notes: &[Note]| notes.iter().copied().collect::<HashSet<_>>();
+        assert_eq!(normal_form(&set(&[C, E, G])), [0, 4, 7]);
+        assert_eq!(normal_form(&set(&[E, G, C])), [0, 4, 7]);
+        assert_eq!(normal_form(&set(&[A, C, E])), [9, 0, 4]);
+        assert_eq!(prime_form(&set(&[C, E, G])), [0, 3, 7]);
+        assert_eq!(prime_form(&set(&[A, C, E])), [0, 3, 7]);
+        assert_eq!(prime_form(&set(&[C, Cs, D])), [0, 1, 2]);
+        assert_eq!(prime_form(&set(&[G, B, D, F])), [0, 2, 5, 8]);
+        assert_eq!(prime_form(&set(&[C, E, Gs])), [0, 4, 8]);
+        assert_eq!(prime_form(&set(&[])), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_interval_vector() {
+        let major = [C, D, E, F, G, A, B].into_iter().collect();
+        assert_eq!(interval_vector(&major), [2, 5, 4, 3, 6, 1]);
+        let diminished = [C, Ds, Fs, A].into_iter().collect();
+        assert_eq!(interval_vector(&diminished), [0, 0, 4, 0, 0, 2]);
+        assert_eq!(interval_vector(&HashSet::new()), [0; 6]);
+    }
+}